@@ -1,29 +1,58 @@
-use minifb::Window;
-use crate::math::{Vec3, Mat4};
+use crate::math::{Vec3, DVec3, Quat};
+use crate::physics::resolve_camera_collisions;
+use super::{Camera, CameraContext, CameraInput};
+
+/// Cómo se orienta la cámara libre
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlightMode {
+    /// Yaw/pitch con el mundo como arriba (estilo FPS, pitch limitado)
+    Fps,
+    /// Orientación completa por cuaternión: roll y loops libres
+    SixDof,
+}
 
 pub struct FreeCamera {
-    pub position: Vec3,
+    pub position: DVec3,
     pub yaw: f32,
     pub pitch: f32,
     pub speed: f32,
     pub mouse_sensitivity: f32,
+    pub mode: FlightMode,
+    pub orientation: Quat, // solo en `SixDof`
+    pub roll_speed: f32,   // rad/s
 }
 
 impl FreeCamera {
-    pub fn new(position: Vec3) -> Self {
+    pub fn new(position: DVec3) -> Self {
         Self {
             position,
             yaw: 0.0,
             pitch: 0.0,
             speed: 12.0,
             mouse_sensitivity: 0.002,
+            mode: FlightMode::Fps,
+            orientation: Quat::identity(),
+            roll_speed: 1.5,
+        }
+    }
+
+    /// Cambia de modo conservando hacia dónde mira (al volver a FPS se pierde el roll)
+    pub fn set_mode(&mut self, mode: FlightMode) {
+        if mode == self.mode {
+            return;
         }
+        let forward = self.forward();
+        self.mode = mode;
+        self.look_along(forward);
     }
 
     // ----------------------------
     // DIRECCIONES
     // ----------------------------
     pub fn forward(&self) -> Vec3 {
+        if self.mode == FlightMode::SixDof {
+            return self.orientation.forward();
+        }
         Vec3::new(
             self.yaw.sin() * self.pitch.cos(),
             -self.pitch.sin(),
@@ -32,45 +61,178 @@ impl FreeCamera {
     }
 
     pub fn right(&self) -> Vec3 {
+        if self.mode == FlightMode::SixDof {
+            return self.orientation.rotate(Vec3::unit_x());
+        }
         Vec3::new(self.yaw.cos(), 0.0, -self.yaw.sin()).normalize()
     }
 
     pub fn up(&self) -> Vec3 {
+        if self.mode == FlightMode::SixDof {
+            return self.orientation.up();
+        }
         self.right().cross(&self.forward()).normalize()
     }
 
+    /// Orienta la cámara hacia `dir` (en modo FPS el roll no se puede representar)
+    pub fn look_along(&mut self, dir: Vec3) {
+        let d = dir.normalize();
+        self.pitch = (-d.y).clamp(-1.0, 1.0).asin();
+        self.yaw = d.x.atan2(d.z);
+        self.orientation = Quat::look_rotation(d, Vec3::unit_y());
+    }
+
+    /// Arriba de la vista: el del mundo en FPS, el local en 6DOF
+    fn view_up(&self) -> Vec3 {
+        match self.mode {
+            FlightMode::Fps => Vec3::unit_y(),
+            FlightMode::SixDof => self.orientation.up(),
+        }
+    }
+
     // ----------------------------
-    // MOVIMIENTOS (compatibilidad con InputController)
+    // MOVIMIENTOS
     // ----------------------------
-    pub fn move_forward(&mut self, amount: f32) {
-        self.position = self.position + self.forward() * amount;
+    pub fn move_up(&mut self, amount: f32) {
+        self.position.y += amount as f64;
     }
+}
 
-    pub fn move_backward(&mut self, amount: f32) {
-        self.position = self.position - self.forward() * amount;
+impl Camera for FreeCamera {
+    fn name(&self) -> &'static str {
+        "Libre"
     }
 
-    pub fn move_left(&mut self, amount: f32) {
-        self.position = self.position - self.right() * amount;
+    /// Vuela con el teclado y mira con arrastre del mouse; no entra en los cuerpos
+    fn update(&mut self, input: &CameraInput, ctx: &CameraContext) {
+        if input.toggle_mode {
+            self.set_mode(match self.mode {
+                FlightMode::Fps => FlightMode::SixDof,
+                FlightMode::SixDof => FlightMode::Fps,
+            });
+        }
+
+        let (dx, dy) = if input.dragging { input.mouse_delta } else { (0.0, 0.0) };
+        match self.mode {
+            FlightMode::Fps => {
+                self.yaw += dx * self.mouse_sensitivity;
+                self.pitch = (self.pitch + dy * self.mouse_sensitivity).clamp(-1.5, 1.5);
+            }
+            FlightMode::SixDof => {
+                // Giros en ejes locales: yaw sobre el arriba local, pitch sobre el lateral
+                let yaw = Quat::from_axis_angle(Vec3::unit_y(), dx * self.mouse_sensitivity);
+                let pitch = Quat::from_axis_angle(Vec3::unit_x(), dy * self.mouse_sensitivity);
+                let roll = Quat::from_axis_angle(Vec3::unit_z(), input.roll * self.roll_speed * ctx.dt);
+                self.orientation = (self.orientation * yaw * pitch * roll).normalize();
+            }
+        }
+
+        let speed = self.speed * if input.boost { 3.0 } else { 1.0 } * ctx.dt;
+        // `right()` apunta a la izquierda de la pantalla, igual que +X de la entrada
+        self.position = self.position
+            + self.forward() * (input.movement.z * speed)
+            + self.right() * (input.movement.x * speed);
+        match self.mode {
+            FlightMode::Fps => self.move_up(input.movement.y * speed),
+            FlightMode::SixDof => self.position = self.position + self.up() * (input.movement.y * speed),
+        }
+
+        resolve_camera_collisions(ctx.system, &mut self.position, 1.2);
     }
 
-    pub fn move_right(&mut self, amount: f32) {
-        self.position = self.position + self.right() * amount;
+    fn position(&self) -> DVec3 {
+        self.position
     }
 
-    pub fn move_up(&mut self, amount: f32) {
-        self.position.y += amount;
+    fn forward(&self) -> Vec3 {
+        FreeCamera::forward(self)
     }
 
-    pub fn move_down(&mut self, amount: f32) {
-        self.position.y -= amount;
+    fn up(&self) -> Vec3 {
+        self.view_up()
     }
+}
 
-    // ----------------------------
-    // VIEW MATRIX
-    // ----------------------------
-    pub fn view_matrix(&self) -> Mat4 {
-        let target = self.position + self.forward();
-        Mat4::look_at(self.position, target, Vec3::unit_y())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_ship;
+    use crate::scene::solar_system::test_system;
+
+    fn run(camera: &mut FreeCamera, input: CameraInput, frames: usize) {
+        // Sistema sin cuerpos cerca: la cámara empieza lejos
+        let system = test_system();
+        let ship = test_ship();
+        for _ in 0..frames {
+            camera.update(&input, &CameraContext { system: &system, ship: &ship, dt: 0.01 });
+        }
+    }
+
+    fn far_camera() -> FreeCamera {
+        FreeCamera::new(DVec3::new(0.0, 1000.0, 0.0))
+    }
+
+    #[test]
+    fn test_fps_mode_clamps_pitch() {
+        let mut camera = far_camera();
+        let drag_down = CameraInput { dragging: true, mouse_delta: (0.0, 100.0), ..Default::default() };
+        run(&mut camera, drag_down, 100);
+        assert_eq!(camera.pitch, 1.5);
+        assert!(Camera::up(&camera) == Vec3::unit_y());
+    }
+
+    #[test]
+    fn test_six_dof_loops_over_the_top() {
+        let mut camera = far_camera();
+        camera.set_mode(FlightMode::SixDof);
+
+        // Una vuelta completa de pitch en 40 frames, pasando por la vertical
+        let step = std::f32::consts::TAU / 40.0 / camera.mouse_sensitivity;
+        let drag = CameraInput { dragging: true, mouse_delta: (0.0, -step), ..Default::default() };
+        let mut min_forward_z: f32 = 1.0;
+        let mut max_forward_y: f32 = -1.0;
+        for _ in 0..40 {
+            run(&mut camera, drag, 1);
+            min_forward_z = min_forward_z.min(camera.forward().z);
+            max_forward_y = max_forward_y.max(camera.forward().y);
+        }
+        assert!(max_forward_y > 0.99);  // miró hacia arriba
+        assert!(min_forward_z < -0.99); // y quedó de espaldas, invertida
+        assert!((camera.forward() - Vec3::unit_z()).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_six_dof_roll_and_local_movement() {
+        let mut camera = far_camera();
+        camera.set_mode(FlightMode::SixDof);
+
+        // Un cuarto de vuelta de roll: el arriba local queda horizontal
+        let roll = CameraInput { roll: 1.0, ..Default::default() };
+        let frames = (std::f32::consts::FRAC_PI_2 / (camera.roll_speed * 0.01)).round() as usize;
+        run(&mut camera, roll, frames);
+        let up = Camera::up(&camera);
+        assert!(up.y.abs() < 0.02);
+        assert!((camera.forward() - Vec3::unit_z()).length() < 1e-4);
+
+        // Subir se mueve sobre el arriba local, no sobre el del mundo
+        let start = camera.position;
+        let rise = CameraInput { movement: Vec3::unit_y(), ..Default::default() };
+        run(&mut camera, rise, 10);
+        let moved = (camera.position - start).to_vec3().normalize();
+        assert!((moved - up).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_mode_switch_keeps_heading() {
+        let mut camera = far_camera();
+        camera.yaw = 0.7;
+        camera.pitch = -0.3;
+        let before = camera.forward();
+
+        camera.set_mode(FlightMode::SixDof);
+        assert!((camera.forward() - before).length() < 1e-4);
+
+        camera.set_mode(FlightMode::Fps);
+        assert!((camera.forward() - before).length() < 1e-4);
     }
 }
@@ -1,3 +1,67 @@
 pub mod freecam;
+pub mod orbit;
+pub mod path;
+pub mod ship;
 
 pub use freecam::*;
+pub use orbit::*;
+pub use path::*;
+pub use ship::*;
+
+use crate::math::{Vec3, DVec3, Mat4};
+use crate::models::SpaceShip;
+use crate::scene::solar_system::SolarSystem;
+
+/// Entrada de un frame, ya traducida del teclado y el mouse
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CameraInput {
+    pub mouse_delta: (f32, f32), // píxeles desde el frame anterior
+    pub dragging: bool,          // botón derecho apretado
+    pub scroll: f32,             // rueda: positivo = acercar
+    pub movement: Vec3,          // ejes de la cámara: +X izquierda, +Y arriba, +Z adelante
+    pub roll: f32,               // [-1, 1]
+    pub boost: bool,
+    pub toggle_mode: bool,       // alternar el modo propio de la cámara
+}
+
+/// Lo que una cámara puede seguir
+pub struct CameraContext<'a> {
+    pub system: &'a SolarSystem,
+    pub ship: &'a SpaceShip,
+    pub dt: f32,
+}
+
+/// Modo de cámara intercambiable en tiempo de ejecución
+pub trait Camera {
+    fn name(&self) -> &'static str;
+
+    fn update(&mut self, input: &CameraInput, ctx: &CameraContext);
+
+    fn position(&self) -> DVec3;
+
+    fn forward(&self) -> Vec3;
+
+    fn up(&self) -> Vec3 {
+        Vec3::unit_y()
+    }
+
+    /// Matriz de vista relativa a la cámara (solo rotación)
+    fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at(Vec3::zero(), self.forward(), self.up())
+    }
+
+    /// ¿Teclado y mouse pilotan la nave en este modo?
+    fn controls_ship(&self) -> bool {
+        false
+    }
+
+    /// Campo de visión propio (grados), si la cámara lo controla
+    fn fov_deg(&self) -> Option<f32> {
+        None
+    }
+
+    /// ¿Se dibuja la nave? (desde la cabina estaría alrededor de la cámara)
+    fn shows_ship(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,168 @@
+use crate::math::{Vec3, DVec3, smooth_step};
+use super::{Camera, CameraContext, CameraInput};
+
+/// Cámara que gira alrededor de un cuerpo y lo mantiene centrado mientras orbita.
+/// Sigue al cuerpo seleccionado; al cambiar de objetivo el foco viaja suavemente
+pub struct OrbitCamera {
+    pub target: usize,             // índice como en `SolarSystem::body`
+    pub yaw: f32,
+    pub pitch: f32,
+    pub distance: f32,             // distancia deseada al centro
+    pub min_distance_factor: f32,  // distancia mínima en radios del cuerpo
+    pub max_distance: f32,
+    pub rotate_sensitivity: f32,   // rad por píxel
+    pub zoom_step: f32,            // fracción de distancia por paso de rueda
+    pub transition_time: f32,      // segundos para cambiar de objetivo
+
+    focus: DVec3,
+    current_distance: f32,
+    transition_from: DVec3,
+    transition: f32,               // 0 → 1 durante el cambio de objetivo
+}
+
+impl OrbitCamera {
+    pub fn new(target: usize) -> Self {
+        Self {
+            target,
+            yaw: 0.0,
+            pitch: 0.35,
+            distance: 20.0,
+            min_distance_factor: 1.5,
+            max_distance: 500.0,
+            rotate_sensitivity: 0.005,
+            zoom_step: 0.1,
+            transition_time: 1.0,
+            focus: DVec3::zero(),
+            current_distance: 20.0,
+            transition_from: DVec3::zero(),
+            transition: 1.0,
+        }
+    }
+
+    /// Cambia de objetivo partiendo del foco actual
+    pub fn set_target(&mut self, target: usize) {
+        if target != self.target {
+            self.target = target;
+            self.transition_from = self.focus;
+            self.transition = 0.0;
+        }
+    }
+
+    fn direction(&self) -> Vec3 {
+        Vec3::new(
+            self.yaw.sin() * self.pitch.cos(),
+            -self.pitch.sin(),
+            self.yaw.cos() * self.pitch.cos(),
+        )
+    }
+}
+
+impl Camera for OrbitCamera {
+    fn name(&self) -> &'static str {
+        "Órbita"
+    }
+
+    fn update(&mut self, input: &CameraInput, ctx: &CameraContext) {
+        if let Some(selected) = ctx.system.selected {
+            self.set_target(selected);
+        }
+        let Some(body) = ctx.system.body(self.target) else {
+            return;
+        };
+
+        // Arrastre: girar; rueda: zoom multiplicativo
+        if input.dragging {
+            self.yaw += input.mouse_delta.0 * self.rotate_sensitivity;
+            self.pitch = (self.pitch + input.mouse_delta.1 * self.rotate_sensitivity).clamp(-1.5, 1.5);
+        }
+        let min_distance = body.radius * self.min_distance_factor;
+        self.distance = (self.distance * (1.0 - input.scroll * self.zoom_step)).clamp(min_distance, self.max_distance);
+
+        // Foco: del objetivo anterior al nuevo, que sigue moviéndose
+        self.transition = (self.transition + ctx.dt / self.transition_time.max(1e-3)).min(1.0);
+        let t = smooth_step(self.transition) as f64;
+        self.focus = self.transition_from.lerp(&body.position(), t);
+
+        let k = 1.0 - (-8.0 * ctx.dt).exp();
+        self.current_distance += (self.distance - self.current_distance) * k;
+        self.current_distance = self.current_distance.max(min_distance);
+    }
+
+    fn position(&self) -> DVec3 {
+        self.focus + self.direction() * -self.current_distance
+    }
+
+    fn forward(&self) -> Vec3 {
+        self.direction()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_ship;
+    use crate::scene::solar_system::{test_system, SolarSystem};
+
+    fn run(camera: &mut OrbitCamera, system: &mut SolarSystem, input: CameraInput, seconds: f32) {
+        let ship = test_ship();
+        let dt = 1.0 / 60.0;
+        for _ in 0..(seconds / dt) as usize {
+            system.update(dt);
+            camera.update(&input, &CameraContext { system, ship: &ship, dt });
+        }
+    }
+
+    #[test]
+    fn test_keeps_moving_target_centered() {
+        let mut system = test_system();
+        let mut camera = OrbitCamera::new(2);
+        run(&mut camera, &mut system, CameraInput::default(), 3.0);
+
+        let body = system.planets[1].position();
+        let to_body = (body - camera.position()).normalize().to_vec3();
+        assert!((to_body - camera.forward()).length() < 1e-4);
+        assert!(((body - camera.position()).length() - 20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_zoom_respects_body_radius() {
+        let mut system = test_system();
+        let mut camera = OrbitCamera::new(0);
+        let zoom_in = CameraInput { scroll: 5.0, ..Default::default() };
+        run(&mut camera, &mut system, zoom_in, 2.0);
+
+        let min = system.sun.radius * camera.min_distance_factor;
+        assert_eq!(camera.distance, min);
+        assert!((camera.position() - system.sun.position()).length() >= min as f64 - 1e-4);
+    }
+
+    #[test]
+    fn test_smooth_target_switch() {
+        let mut system = test_system();
+        let mut camera = OrbitCamera::new(0);
+        run(&mut camera, &mut system, CameraInput::default(), 1.5);
+        assert!((camera.focus - system.sun.position()).length() < 1e-6);
+
+        // Selección nueva: a mitad de la transición el foco está entre ambos
+        system.selected = Some(3);
+        run(&mut camera, &mut system, CameraInput::default(), 0.5);
+        let to_planet = (camera.focus - system.planets[2].position()).length();
+        assert!(to_planet > 1.0 && camera.focus.length() > 1.0);
+
+        run(&mut camera, &mut system, CameraInput::default(), 1.0);
+        assert!((camera.focus - system.planets[2].position()).length() < 1e-6);
+    }
+
+    #[test]
+    fn test_drag_rotates_around_target() {
+        let mut system = test_system();
+        let mut camera = OrbitCamera::new(0);
+        run(&mut camera, &mut system, CameraInput::default(), 1.0);
+        let before = camera.position();
+
+        let drag = CameraInput { dragging: true, mouse_delta: (10.0, 0.0), ..Default::default() };
+        run(&mut camera, &mut system, drag, 0.5);
+        assert!((camera.position() - before).length() > 1.0);
+        assert!(((camera.position() - system.sun.position()).length() - 20.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,498 @@
+use std::fmt::Write as _;
+use crate::math::{Vec3, DVec3, Quat, smooth_step, catmull_rom, bezier};
+use crate::scene::solar_system::SolarSystem;
+use super::{Camera, CameraContext, CameraInput};
+
+/// Curva que recorre la posición entre keyframes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PositionCurve {
+    CatmullRom,
+    /// Bézier cúbica por tramo, con asas de un tercio del tramo hacia los vecinos
+    Bezier,
+}
+
+/// Interpolación de la orientación entre keyframes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RotationBlend {
+    Slerp,
+    Squad,
+}
+
+/// Curva de tiempo aplicada a todo el recorrido
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseInOut,
+}
+
+/// Orientación de un keyframe: fija o mirando a un cuerpo que se mueve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyOrientation {
+    Fixed(Quat),
+    LookAt(usize), // índice como en `SolarSystem::body`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub position: DVec3,
+    pub orientation: KeyOrientation,
+    pub fov: f32, // grados
+}
+
+/// Estado de la cámara en un instante del recorrido
+#[derive(Debug, Clone, Copy)]
+pub struct PathSample {
+    pub position: DVec3,
+    pub orientation: Quat,
+    pub fov: f32,
+}
+
+/// Recorrido de cámara por keyframes ordenados por tiempo
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraPath {
+    pub keyframes: Vec<Keyframe>,
+    pub curve: PositionCurve,
+    pub rotation: RotationBlend,
+    pub easing: Easing,
+}
+
+impl CameraPath {
+    pub fn new() -> Self {
+        Self {
+            keyframes: Vec::new(),
+            curve: PositionCurve::CatmullRom,
+            rotation: RotationBlend::Squad,
+            easing: Easing::EaseInOut,
+        }
+    }
+
+    /// Agrega un keyframe manteniendo el orden por tiempo
+    pub fn push(&mut self, key: Keyframe) {
+        let at = self.keyframes.partition_point(|k| k.time <= key.time);
+        self.keyframes.insert(at, key);
+    }
+
+    pub fn duration(&self) -> f32 {
+        match (self.keyframes.first(), self.keyframes.last()) {
+            (Some(a), Some(b)) => b.time - a.time,
+            _ => 0.0,
+        }
+    }
+
+    /// Muestrea el recorrido en `time` (segundos desde el primer keyframe).
+    /// Los keyframes `LookAt` miran al cuerpo en su posición actual
+    pub fn sample(&self, time: f32, system: &SolarSystem) -> Option<PathSample> {
+        let first = self.keyframes.first()?;
+        let n = self.keyframes.len();
+        if n == 1 {
+            let position = first.position;
+            return Some(PathSample { position, orientation: resolve(first, position, system), fov: first.fov });
+        }
+
+        let duration = self.duration();
+        let mut s = if duration > 0.0 { (time / duration).clamp(0.0, 1.0) } else { 1.0 };
+        if self.easing == Easing::EaseInOut {
+            s = smooth_step(s);
+        }
+        let t = first.time + s * duration;
+
+        // Tramo [i, i + 1] que contiene a t
+        let i = self.keyframes.partition_point(|k| k.time <= t).clamp(1, n - 1) - 1;
+        let (k0, k1, k2, k3) = (
+            &self.keyframes[i.saturating_sub(1)],
+            &self.keyframes[i],
+            &self.keyframes[i + 1],
+            &self.keyframes[(i + 2).min(n - 1)],
+        );
+        let span = k2.time - k1.time;
+        let u = if span > 0.0 { ((t - k1.time) / span).clamp(0.0, 1.0) } else { 1.0 };
+
+        let (p0, p1, p2, p3) = (k0.position, k1.position, k2.position, k3.position);
+        let position = match self.curve {
+            PositionCurve::CatmullRom => catmull_rom(p0, p1, p2, p3, u as f64),
+            PositionCurve::Bezier => {
+                let handle = (p2 - p1).length() / 3.0;
+                let c1 = p1 + tangent(p0, p2) * handle;
+                let c2 = p2 - tangent(p1, p3) * handle;
+                bezier(p1, c1, c2, p2, u as f64)
+            }
+        };
+
+        // Cuaterniones en el mismo hemisferio para interpolar por el camino corto
+        let q1 = resolve(k1, position, system);
+        let q0 = same_hemisphere(resolve(k0, position, system), &q1);
+        let q2 = same_hemisphere(resolve(k2, position, system), &q1);
+        let q3 = same_hemisphere(resolve(k3, position, system), &q2);
+        let orientation = match self.rotation {
+            RotationBlend::Slerp => q1.slerp(&q2, u),
+            RotationBlend::Squad => {
+                let s1 = q1.squad_control(&q0, &q2);
+                let s2 = q2.squad_control(&q1, &q3);
+                q1.squad(&q2, &s1, &s2, u)
+            }
+        };
+
+        Some(PathSample {
+            position,
+            orientation: orientation.normalize(),
+            fov: k1.fov + (k2.fov - k1.fov) * u,
+        })
+    }
+
+    // ----------------------------
+    // ARCHIVO DE TEXTO
+    // ----------------------------
+    /// Formato de texto, una línea por dato:
+    /// `curve catmull-rom|bezier`, `rotation slerp|squad`, `easing linear|ease-in-out`,
+    /// `key <t> <x> <y> <z> quat <qx> <qy> <qz> <qw> <fov>` o
+    /// `key <t> <x> <y> <z> look <cuerpo> <fov>`
+    pub fn to_text(&self) -> String {
+        let mut out = String::from("# recorrido de cámara\n");
+        let curve = match self.curve {
+            PositionCurve::CatmullRom => "catmull-rom",
+            PositionCurve::Bezier => "bezier",
+        };
+        let rotation = match self.rotation {
+            RotationBlend::Slerp => "slerp",
+            RotationBlend::Squad => "squad",
+        };
+        let easing = match self.easing {
+            Easing::Linear => "linear",
+            Easing::EaseInOut => "ease-in-out",
+        };
+        let _ = writeln!(out, "curve {curve}\nrotation {rotation}\neasing {easing}");
+
+        for k in &self.keyframes {
+            let p = k.position;
+            let _ = write!(out, "key {} {} {} {} ", k.time, p.x, p.y, p.z);
+            let _ = match k.orientation {
+                KeyOrientation::Fixed(q) => writeln!(out, "quat {} {} {} {} {}", q.x, q.y, q.z, q.w, k.fov),
+                KeyOrientation::LookAt(body) => writeln!(out, "look {} {}", body, k.fov),
+            };
+        }
+        out
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut path = Self::new();
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |what: &str| format!("línea {}: {}", n + 1, what);
+            let parts: Vec<&str> = line.split_whitespace().collect();
+
+            match parts.as_slice() {
+                ["curve", "catmull-rom"] => path.curve = PositionCurve::CatmullRom,
+                ["curve", "bezier"] => path.curve = PositionCurve::Bezier,
+                ["rotation", "slerp"] => path.rotation = RotationBlend::Slerp,
+                ["rotation", "squad"] => path.rotation = RotationBlend::Squad,
+                ["easing", "linear"] => path.easing = Easing::Linear,
+                ["easing", "ease-in-out"] => path.easing = Easing::EaseInOut,
+                ["key", t, x, y, z, rest @ ..] => {
+                    let f = |s: &str| s.parse::<f32>().map_err(|_| err("número inválido"));
+                    let d = |s: &str| s.parse::<f64>().map_err(|_| err("número inválido"));
+                    let position = DVec3::new(d(x)?, d(y)?, d(z)?);
+
+                    let (orientation, fov) = match rest {
+                        ["quat", qx, qy, qz, qw, fov] => (
+                            KeyOrientation::Fixed(Quat::new(f(qx)?, f(qy)?, f(qz)?, f(qw)?).normalize()),
+                            f(fov)?,
+                        ),
+                        ["look", body, fov] => (
+                            KeyOrientation::LookAt(body.parse().map_err(|_| err("cuerpo inválido"))?),
+                            f(fov)?,
+                        ),
+                        _ => return Err(err("orientación inválida")),
+                    };
+                    path.push(Keyframe { time: f(t)?, position, orientation, fov });
+                }
+                _ => return Err(err("línea desconocida")),
+            }
+        }
+        Ok(path)
+    }
+
+    pub fn save(&self, file: &str) -> Result<(), String> {
+        if let Some(dir) = std::path::Path::new(file).parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(file, self.to_text()).map_err(|e| e.to_string())
+    }
+
+    pub fn load(file: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(file).map_err(|e| e.to_string())?;
+        Self::from_text(&text)
+    }
+}
+
+fn resolve(key: &Keyframe, from: DVec3, system: &SolarSystem) -> Quat {
+    match key.orientation {
+        KeyOrientation::Fixed(q) => q,
+        KeyOrientation::LookAt(index) => match system.body(index) {
+            Some(body) => Quat::look_rotation((body.position() - from).to_vec3(), Vec3::unit_y()),
+            None => Quat::identity(),
+        },
+    }
+}
+
+fn same_hemisphere(q: Quat, reference: &Quat) -> Quat {
+    if q.dot(reference) < 0.0 { -q } else { q }
+}
+
+fn tangent(prev: DVec3, next: DVec3) -> DVec3 {
+    let d = next - prev;
+    if d.length_squared() == 0.0 { d } else { d.normalize() }
+}
+
+// =========================================
+// Reproducción
+// =========================================
+
+/// Cámara que reproduce un `CameraPath`
+pub struct PathCamera {
+    pub path: CameraPath,
+    time: f32,
+    sample: Option<PathSample>,
+}
+
+impl PathCamera {
+    pub fn new(path: CameraPath) -> Self {
+        Self { path, time: 0.0, sample: None }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.time >= self.path.duration()
+    }
+}
+
+impl Camera for PathCamera {
+    fn name(&self) -> &'static str {
+        "Recorrido"
+    }
+
+    fn update(&mut self, _input: &CameraInput, ctx: &CameraContext) {
+        // El primer frame muestra el keyframe inicial
+        if self.sample.is_some() {
+            self.time += ctx.dt;
+        }
+        self.sample = self.path.sample(self.time, ctx.system);
+    }
+
+    fn position(&self) -> DVec3 {
+        self.sample.map_or(DVec3::zero(), |s| s.position)
+    }
+
+    fn forward(&self) -> Vec3 {
+        self.sample.map_or(Vec3::unit_z(), |s| s.orientation.forward())
+    }
+
+    fn up(&self) -> Vec3 {
+        self.sample.map_or(Vec3::unit_y(), |s| s.orientation.up())
+    }
+
+    fn fov_deg(&self) -> Option<f32> {
+        self.sample.map(|s| s.fov)
+    }
+}
+
+// =========================================
+// Grabación
+// =========================================
+
+/// Graba keyframes de una cámara en vivo a intervalos fijos
+pub struct PathRecorder {
+    pub path: CameraPath,
+    pub interval: f32,
+    clock: f32,
+    since_last: f32,
+}
+
+impl PathRecorder {
+    pub fn new(interval: f32) -> Self {
+        Self { path: CameraPath::new(), interval, clock: 0.0, since_last: f32::INFINITY }
+    }
+
+    /// Avanza el reloj y graba un keyframe si toca
+    pub fn update(&mut self, dt: f32, camera: &dyn Camera, fov: f32) {
+        if self.since_last >= self.interval {
+            self.add_key(camera, fov, None);
+        }
+        self.clock += dt;
+        self.since_last += dt;
+    }
+
+    /// Keyframe en el instante actual; con `look_at` mira a ese cuerpo
+    pub fn add_key(&mut self, camera: &dyn Camera, fov: f32, look_at: Option<usize>) {
+        let orientation = match look_at {
+            Some(body) => KeyOrientation::LookAt(body),
+            None => KeyOrientation::Fixed(Quat::look_rotation(camera.forward(), camera.up())),
+        };
+        // Un keyframe manual reemplaza al automático del mismo instante
+        self.path.keyframes.retain(|k| k.time != self.clock);
+        self.path.push(Keyframe { time: self.clock, position: camera.position(), orientation, fov });
+        self.since_last = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_ship;
+    use crate::scene::solar_system::test_system;
+    use crate::camera::FreeCamera;
+
+    fn key(time: f32, x: f64, yaw: f32) -> Keyframe {
+        Keyframe {
+            time,
+            position: DVec3::new(x, 0.0, 0.0),
+            orientation: KeyOrientation::Fixed(Quat::from_axis_angle(Vec3::unit_y(), yaw)),
+            fov: 60.0 + time * 10.0,
+        }
+    }
+
+    fn path(curve: PositionCurve, rotation: RotationBlend, easing: Easing) -> CameraPath {
+        let mut p = CameraPath::new();
+        p.curve = curve;
+        p.rotation = rotation;
+        p.easing = easing;
+        for (i, x) in [0.0, 10.0, 30.0, 35.0].into_iter().enumerate() {
+            p.push(key(i as f32, x, i as f32 * 0.5));
+        }
+        p
+    }
+
+    #[test]
+    fn test_passes_through_keyframes() {
+        let system = test_system();
+        for curve in [PositionCurve::CatmullRom, PositionCurve::Bezier] {
+            for rotation in [RotationBlend::Slerp, RotationBlend::Squad] {
+                let p = path(curve, rotation, Easing::Linear);
+                for k in &p.keyframes {
+                    let s = p.sample(k.time, &system).unwrap();
+                    assert!((s.position - k.position).length() < 1e-6);
+                    assert!((s.fov - k.fov).abs() < 1e-4);
+                    let KeyOrientation::Fixed(q) = k.orientation else { unreachable!() };
+                    assert!(s.orientation.angle_to(&q) < 1e-3);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_curve_is_smooth_between_keys() {
+        let system = test_system();
+        let p = path(PositionCurve::CatmullRom, RotationBlend::Squad, Easing::Linear);
+
+        // Sin saltos: muestras cercanas quedan cerca
+        let mut prev = p.sample(0.0, &system).unwrap().position;
+        for i in 1..=300 {
+            let s = p.sample(i as f32 * 0.01, &system).unwrap().position;
+            assert!((s - prev).length() < 0.5);
+            prev = s;
+        }
+    }
+
+    #[test]
+    fn test_ease_in_out_starts_slow() {
+        let system = test_system();
+        let linear = path(PositionCurve::CatmullRom, RotationBlend::Slerp, Easing::Linear);
+        let eased = path(PositionCurve::CatmullRom, RotationBlend::Slerp, Easing::EaseInOut);
+
+        let start = DVec3::zero();
+        let a = (linear.sample(0.1, &system).unwrap().position - start).length();
+        let b = (eased.sample(0.1, &system).unwrap().position - start).length();
+        assert!(b < a * 0.5);
+
+        // El final es el mismo
+        let end_a = linear.sample(3.0, &system).unwrap().position;
+        let end_b = eased.sample(3.0, &system).unwrap().position;
+        assert!((end_a - end_b).length() < 1e-9);
+    }
+
+    #[test]
+    fn test_look_at_tracks_moving_body() {
+        let mut system = test_system();
+        let mut p = CameraPath::new();
+        for (t, z) in [(0.0, -50.0), (5.0, 50.0)] {
+            p.push(Keyframe {
+                time: t,
+                position: DVec3::new(0.0, 40.0, z),
+                orientation: KeyOrientation::LookAt(2),
+                fov: 60.0,
+            });
+        }
+
+        for _ in 0..3 {
+            system.update(1.0);
+            let s = p.sample(2.5, &system).unwrap();
+            let to_body = (system.planets[1].position() - s.position).normalize().to_vec3();
+            assert!((s.orientation.forward() - to_body).length() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_text_roundtrip_and_errors() {
+        let mut p = path(PositionCurve::Bezier, RotationBlend::Slerp, Easing::Linear);
+        p.push(Keyframe {
+            time: 4.5,
+            position: DVec3::new(1.0, 2.0, 3.0),
+            orientation: KeyOrientation::LookAt(3),
+            fov: 45.0,
+        });
+
+        let back = CameraPath::from_text(&p.to_text()).unwrap();
+        assert_eq!(back, p);
+
+        assert!(CameraPath::from_text("key 0 1 2 3 look x 60").is_err());
+        assert!(CameraPath::from_text("velocidad 3").is_err());
+
+        // Formato del archivo que se graba con K, con comentarios y valores por defecto
+        let demo = CameraPath::from_text(
+            "# recorrido\n\
+             curve catmull-rom\n\
+             rotation squad\n\
+             key 0 0 12 60 look 0 60\n\
+             \n\
+             key 12 20 4 -25 look 2 50\n\
+             key 24 -10 25 45 look 0 65\n",
+        )
+        .unwrap();
+        assert_eq!(demo.keyframes.len(), 3);
+        assert_eq!(demo.duration(), 24.0);
+    }
+
+    #[test]
+    fn test_record_and_play_back() {
+        let system = test_system();
+        let ship = test_ship();
+        let mut free = FreeCamera::new(DVec3::new(0.0, 0.0, -80.0));
+        let mut recorder = PathRecorder::new(0.5);
+
+        // Vuela hacia adelante mientras gira
+        let dt = 0.1;
+        let input = CameraInput { movement: Vec3::unit_z(), ..Default::default() };
+        for _ in 0..20 {
+            recorder.update(dt, &free, 60.0);
+            free.yaw += 0.02;
+            free.update(&input, &CameraContext { system: &system, ship: &ship, dt });
+        }
+        assert_eq!(recorder.path.keyframes.len(), 4);
+
+        let mut player = PathCamera::new(recorder.path.clone());
+        let ctx = CameraContext { system: &system, ship: &ship, dt };
+        player.update(&CameraInput::default(), &ctx);
+        let first = recorder.path.keyframes[0];
+        assert!((player.position() - first.position).length() < 1e-6);
+
+        while !player.finished() {
+            player.update(&CameraInput::default(), &ctx);
+        }
+        let last = recorder.path.keyframes.last().unwrap();
+        assert!((player.position() - last.position).length() < 1e-4);
+        assert_eq!(player.fov_deg(), Some(60.0));
+    }
+}
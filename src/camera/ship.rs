@@ -0,0 +1,292 @@
+use crate::math::{Vec3, DVec3, Quat};
+use crate::models::SpaceShip;
+use super::{Camera, CameraContext, CameraInput};
+
+// =========================================
+// Persecución
+// =========================================
+
+/// Tercera persona detrás de la nave. La orientación sigue a la nave con retraso
+/// y la posición cuelga de un resorte críticamente amortiguado, medido respecto
+/// de la nave: la traslación no genera retraso, los giros sí
+pub struct ChaseCamera {
+    pub distance: f32,        // detrás de la nave
+    pub height: f32,          // por encima, en el eje arriba de la nave
+    pub look_ahead: f32,      // punto al que mira, delante de la nave
+    pub stiffness: f32,       // rigidez del resorte de posición (1/s²)
+    pub turn_rate: f32,       // rapidez con que copia la orientación (1/s)
+
+    orientation: Quat,
+    offset: Vec3,             // posición relativa a la nave
+    offset_velocity: Vec3,
+    ship_position: DVec3,
+    initialized: bool,
+}
+
+impl ChaseCamera {
+    pub fn new(distance: f32, height: f32) -> Self {
+        Self {
+            distance,
+            height,
+            look_ahead: 4.0,
+            stiffness: 60.0,
+            turn_rate: 5.0,
+            orientation: Quat::identity(),
+            offset: Vec3::zero(),
+            offset_velocity: Vec3::zero(),
+            ship_position: DVec3::zero(),
+            initialized: false,
+        }
+    }
+
+    /// Desplazamiento de reposo para la orientación actual
+    fn rest_offset(&self) -> Vec3 {
+        self.orientation.rotate(Vec3::new(0.0, self.height, -self.distance))
+    }
+}
+
+impl Camera for ChaseCamera {
+    fn name(&self) -> &'static str {
+        "Persecución"
+    }
+
+    fn update(&mut self, _input: &CameraInput, ctx: &CameraContext) {
+        let ship = ctx.ship;
+        self.ship_position = ship.position;
+
+        if !self.initialized {
+            self.orientation = ship.orientation;
+            self.offset = self.rest_offset();
+            self.initialized = true;
+            return;
+        }
+
+        let dt = ctx.dt;
+        let follow = 1.0 - (-self.turn_rate * dt).exp();
+        self.orientation = self.orientation.slerp(&ship.orientation, follow).normalize();
+
+        // Resorte críticamente amortiguado (Euler semi-implícito)
+        let damping = 2.0 * self.stiffness.sqrt();
+        let accel = (self.rest_offset() - self.offset) * self.stiffness - self.offset_velocity * damping;
+        self.offset_velocity = self.offset_velocity + accel * dt;
+        self.offset = self.offset + self.offset_velocity * dt;
+    }
+
+    fn position(&self) -> DVec3 {
+        self.ship_position + self.offset
+    }
+
+    fn forward(&self) -> Vec3 {
+        let target = self.orientation.forward() * self.look_ahead;
+        (target - self.offset).normalize()
+    }
+
+    fn up(&self) -> Vec3 {
+        self.orientation.up()
+    }
+
+    fn controls_ship(&self) -> bool {
+        true
+    }
+}
+
+// =========================================
+// Cabina
+// =========================================
+
+/// Primera persona desde el asiento de la nave (`SpaceShip::seat`)
+pub struct CockpitCamera {
+    position: DVec3,
+    orientation: Quat,
+}
+
+impl CockpitCamera {
+    pub fn new() -> Self {
+        Self { position: DVec3::zero(), orientation: Quat::identity() }
+    }
+}
+
+impl Camera for CockpitCamera {
+    fn name(&self) -> &'static str {
+        "Cabina"
+    }
+
+    fn update(&mut self, _input: &CameraInput, ctx: &CameraContext) {
+        let ship = ctx.ship;
+        self.orientation = ship.orientation;
+        self.position = ship.position + ship.orientation.rotate(ship.seat);
+    }
+
+    fn position(&self) -> DVec3 {
+        self.position
+    }
+
+    fn forward(&self) -> Vec3 {
+        self.orientation.forward()
+    }
+
+    fn up(&self) -> Vec3 {
+        self.orientation.up()
+    }
+
+    fn controls_ship(&self) -> bool {
+        true
+    }
+
+    fn shows_ship(&self) -> bool {
+        false
+    }
+}
+
+// =========================================
+// Cámara fija externa
+// =========================================
+
+/// Cámara quieta en el espacio que mira a la nave, como una cámara de TV.
+/// Cuando la nave se aleja demasiado se recoloca por delante de su trayectoria
+pub struct FixedCamera {
+    pub max_distance: f32,
+    pub side_offset: f32,     // separación lateral al recolocarse
+    anchor: Option<DVec3>,
+    target: DVec3,
+}
+
+impl FixedCamera {
+    pub fn new(max_distance: f32) -> Self {
+        Self { max_distance, side_offset: 4.0, anchor: None, target: DVec3::zero() }
+    }
+
+    fn place(&mut self, ship: &SpaceShip) {
+        let speed = ship.velocity.length();
+        let ahead = if speed > 0.1 {
+            ship.velocity.normalize() * (self.max_distance as f64 * 0.6)
+        } else {
+            DVec3::from_vec3(ship.forward() * (self.max_distance * 0.3))
+        };
+        let side = DVec3::from_vec3(ship.right() * self.side_offset + ship.up() * (self.side_offset * 0.5));
+        self.anchor = Some(ship.position + ahead + side);
+    }
+}
+
+impl Camera for FixedCamera {
+    fn name(&self) -> &'static str {
+        "Fija"
+    }
+
+    fn update(&mut self, _input: &CameraInput, ctx: &CameraContext) {
+        let ship = ctx.ship;
+        self.target = ship.position;
+
+        let too_far = self
+            .anchor
+            .is_none_or(|a| (a - ship.position).length() > self.max_distance as f64);
+        if too_far {
+            self.place(ship);
+        }
+    }
+
+    fn position(&self) -> DVec3 {
+        self.anchor.unwrap_or(self.target)
+    }
+
+    fn forward(&self) -> Vec3 {
+        let dir = (self.target - self.position()).to_vec3();
+        if dir.length_squared() < 1e-12 {
+            return Vec3::unit_z();
+        }
+        dir.normalize()
+    }
+
+    fn controls_ship(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_ship;
+    use crate::scene::solar_system::{test_system, SolarSystem};
+
+
+    fn step(camera: &mut dyn Camera, ship: &SpaceShip, system: &SolarSystem) {
+        camera.update(&CameraInput::default(), &CameraContext { system, ship, dt: 1.0 / 60.0 });
+    }
+
+    #[test]
+    fn test_chase_rests_behind_ship() {
+        let system = test_system();
+        let mut s = test_ship();
+        s.position = DVec3::new(100.0, 0.0, 0.0);
+        let mut camera = ChaseCamera::new(6.0, 1.0);
+
+        for _ in 0..120 {
+            step(&mut camera, &s, &system);
+        }
+        let expected = s.position + s.forward() * -6.0 + s.up() * 1.0;
+        assert!((camera.position() - expected).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_chase_lags_turns_but_not_translation() {
+        let system = test_system();
+        let mut s = test_ship();
+        let mut camera = ChaseCamera::new(6.0, 1.0);
+        step(&mut camera, &s, &system);
+
+        // Traslación pura: el desplazamiento relativo no cambia
+        s.position = s.position + DVec3::new(0.0, 0.0, 50.0);
+        step(&mut camera, &s, &system);
+        let rest = s.position + s.forward() * -6.0 + s.up() * 1.0;
+        assert!((camera.position() - rest).length() < 1e-4);
+
+        // Giro brusco: la cámara queda atrasada y luego alcanza
+        s.orientation = Quat::from_axis_angle(Vec3::unit_y(), 1.0);
+        step(&mut camera, &s, &system);
+        let rest = s.position + s.forward() * -6.0 + s.up() * 1.0;
+        assert!((camera.position() - rest).length() > 1.0);
+
+        for _ in 0..240 {
+            step(&mut camera, &s, &system);
+        }
+        assert!((camera.position() - rest).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_cockpit_sits_in_ship_and_hides_it() {
+        let system = test_system();
+        let mut s = test_ship();
+        s.position = DVec3::new(3.0, 2.0, 1.0);
+        s.orientation = Quat::from_axis_angle(Vec3::unit_x(), 0.5);
+        let mut camera = CockpitCamera::new();
+        step(&mut camera, &s, &system);
+
+        let seat = s.position + s.orientation.rotate(s.seat);
+        assert!((camera.position() - seat).length() < 1e-6);
+        assert!((camera.forward() - s.forward()).length() < 1e-6);
+        assert!(!camera.shows_ship());
+        assert!(s.seat.y > 0.0);
+    }
+
+    #[test]
+    fn test_fixed_camera_tracks_and_relocates() {
+        let system = test_system();
+        let mut s = test_ship();
+        s.velocity = DVec3::new(0.0, 0.0, 5.0);
+        let mut camera = FixedCamera::new(30.0);
+        step(&mut camera, &s, &system);
+        let anchor = camera.position();
+
+        // Se queda quieta pero sigue mirando a la nave
+        s.position = DVec3::new(0.0, 0.0, 10.0);
+        step(&mut camera, &s, &system);
+        assert_eq!(camera.position(), anchor);
+        let to_ship = (s.position - anchor).normalize().to_vec3();
+        assert!((camera.forward() - to_ship).length() < 1e-5);
+
+        // Demasiado lejos: se recoloca cerca
+        s.position = DVec3::new(0.0, 0.0, 200.0);
+        step(&mut camera, &s, &system);
+        assert!((camera.position() - s.position).length() <= 30.0);
+    }
+}
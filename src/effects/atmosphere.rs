@@ -0,0 +1,152 @@
+use crate::math::{Vec3, smooth_step};
+use crate::framebuffer::lerp_color;
+
+/// Muestras a lo largo de cada rayo dentro de la atmósfera
+const SAMPLES: usize = 8;
+/// Cuánto pasa la luz más allá del terminador (coseno del ángulo con el sol)
+const TERMINATOR_SOFTNESS: f32 = 0.15;
+/// Ancho de la franja de amanecer/atardecer alrededor del terminador
+const SUNSET_WIDTH: f32 = 0.3;
+const SUNSET_COLOR: u32 = 0xFFFF7A30;
+
+/// Capa de atmósfera alrededor de un cuerpo. Las distancias están en radios del cuerpo
+#[derive(Debug, Clone)]
+pub struct Atmosphere {
+    pub thickness: f32,       // alto de la capa sobre la superficie
+    pub scale_height: f32,    // altura en la que la densidad cae a 1/e
+    pub density: f32,         // densidad en la superficie
+    pub rayleigh_color: u32,  // color del cielo de día
+    pub sunset_color: u32,    // color cerca del terminador
+}
+
+impl Atmosphere {
+    /// Atmósfera con una capa de cuatro alturas de escala
+    pub fn new(scale_height: f32, density: f32, rayleigh_color: u32) -> Self {
+        Self {
+            thickness: scale_height * 4.0,
+            scale_height,
+            density,
+            rayleigh_color,
+            sunset_color: SUNSET_COLOR,
+        }
+    }
+
+    /// Radio de la cáscara que envuelve al cuerpo
+    pub fn shell_radius(&self) -> f32 {
+        1.0 + self.thickness
+    }
+
+    /// Luz dispersada hacia el ojo por el rayo `eye + dir * t` (relativo al centro
+    /// del cuerpo, `dir` normalizado) con el sol en la dirección `to_light`.
+    /// Aproxima dispersión simple de Rayleigh: devuelve color e intensidad para
+    /// mezclar de forma aditiva, o `None` si el rayo no toca la atmósfera
+    pub fn scatter(&self, eye: Vec3, dir: Vec3, to_light: Vec3) -> Option<(u32, f32)> {
+        self.scatter_to(eye, dir, to_light, f32::INFINITY)
+    }
+
+    /// Como `scatter`, pero el rayo se corta a `limit` radios del ojo (lo que ya
+    /// haya dibujado delante)
+    pub fn scatter_to(&self, eye: Vec3, dir: Vec3, to_light: Vec3, limit: f32) -> Option<(u32, f32)> {
+        let (t0, t1) = ray_sphere(eye, dir, self.shell_radius())?;
+        let start = t0.max(0.0);
+        // El rayo se corta en la superficie si la toca
+        let end = match ray_sphere(eye, dir, 1.0) {
+            Some((hit, _)) if hit > start => hit,
+            _ => t1,
+        }
+        .min(limit);
+        if end <= start {
+            return None;
+        }
+
+        let step = (end - start) / SAMPLES as f32;
+        let (mut light, mut sunset) = (0.0, 0.0);
+        for i in 0..SAMPLES {
+            let p = eye + dir * (start + step * (i as f32 + 0.5));
+            let height = (p.length() - 1.0).max(0.0);
+            let density = (-height / self.scale_height).exp();
+            let mu = p.normalize().dot(&to_light);
+            let lit = smooth_step((mu + TERMINATOR_SOFTNESS) / (2.0 * TERMINATOR_SOFTNESS));
+
+            let amount = density * lit * step;
+            light += amount;
+            sunset += amount * (1.0 - smooth_step(mu.abs() / SUNSET_WIDTH));
+        }
+        if light <= 0.0 {
+            return None;
+        }
+
+        let cos_theta = dir.dot(&to_light);
+        let phase = 0.75 * (1.0 + cos_theta * cos_theta);
+        let intensity = 1.0 - (-self.density * light * phase).exp();
+        Some((lerp_color(self.rayleigh_color, self.sunset_color, sunset / light), intensity))
+    }
+}
+
+/// Distancias de entrada y salida del rayo en una esfera centrada en el origen
+fn ray_sphere(origin: Vec3, dir: Vec3, radius: f32) -> Option<(f32, f32)> {
+    let b = origin.dot(&dir);
+    let c = origin.length_squared() - radius * radius;
+    let disc = b * b - c;
+    if disc < 0.0 {
+        return None;
+    }
+    let root = disc.sqrt();
+    let far = -b + root;
+    (far > 0.0).then_some((-b - root, far))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toward(eye: Vec3, target: Vec3) -> Vec3 {
+        (target - eye).normalize()
+    }
+
+    #[test]
+    fn test_limb_glows_brighter_than_disk() {
+        let atmosphere = Atmosphere::new(0.03, 4.0, 0xFF5C9DFF);
+        let eye = Vec3::new(0.0, 0.0, 10.0);
+        let sun = Vec3::unit_z();
+
+        let (_, center) = atmosphere.scatter(eye, toward(eye, Vec3::zero()), sun).unwrap();
+        let (_, limb) = atmosphere.scatter(eye, toward(eye, Vec3::new(0.0, 1.02, 0.0)), sun).unwrap();
+        assert!(limb > center * 2.0, "limbo {limb}, centro {center}");
+
+        // Fuera de la cáscara no hay nada
+        assert!(atmosphere.scatter(eye, toward(eye, Vec3::new(0.0, 2.0, 0.0)), sun).is_none());
+    }
+
+    #[test]
+    fn test_night_side_is_dark() {
+        let atmosphere = Atmosphere::new(0.03, 4.0, 0xFF5C9DFF);
+        let eye = Vec3::new(0.0, 0.0, 10.0);
+        assert!(atmosphere.scatter(eye, toward(eye, Vec3::zero()), -Vec3::unit_z()).is_none());
+    }
+
+    #[test]
+    fn test_terminator_is_reddened() {
+        let atmosphere = Atmosphere::new(0.03, 4.0, 0xFF5C9DFF);
+        let eye = Vec3::new(0.0, 0.0, 10.0);
+        let dir = toward(eye, Vec3::zero());
+
+        let (noon, _) = atmosphere.scatter(eye, dir, Vec3::unit_z()).unwrap();
+        let (dusk, _) = atmosphere.scatter(eye, dir, Vec3::unit_x()).unwrap();
+        assert_eq!(noon, atmosphere.rayleigh_color);
+        assert!((dusk >> 16) & 0xFF > (noon >> 16) & 0xFF);
+        assert!(dusk & 0xFF < noon & 0xFF);
+    }
+
+    #[test]
+    fn test_haze_stops_at_limit() {
+        let atmosphere = Atmosphere::new(0.03, 4.0, 0xFF5C9DFF);
+        // Desde adentro de la capa, mirando al horizonte del lado de día
+        let eye = Vec3::new(0.0, 1.01, 0.0);
+        let (dir, sun) = (Vec3::unit_x(), Vec3::unit_y());
+        let (_, full) = atmosphere.scatter(eye, dir, sun).unwrap();
+        let (_, near) = atmosphere.scatter_to(eye, dir, sun, 0.01).unwrap();
+        assert!(near < full);
+        assert!(atmosphere.scatter_to(eye, dir, sun, 0.0).is_none());
+    }
+}
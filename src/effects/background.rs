@@ -0,0 +1,307 @@
+use crate::effects::skybox::Star;
+use crate::framebuffer::{Framebuffer, pack, unpack};
+use crate::math::{Mat4, Vec3, screen_to_world, smooth_step};
+
+/// Parámetros del fondo procedural
+#[derive(Debug, Clone, Copy)]
+pub struct NebulaSettings {
+    pub seed: u64,
+    pub width: usize,           // textura equirectangular de width x height (2:1)
+    pub height: usize,
+    pub base: u32,              // color del espacio vacío
+    pub palette: [u32; 3],      // nebulosas: de la parte tenue a la más densa
+    pub milky_way: f32,         // intensidad de la banda de la Vía Láctea
+    pub nebula: f32,            // intensidad de las nebulosas
+}
+
+impl Default for NebulaSettings {
+    fn default() -> Self {
+        Self {
+            seed: 1,
+            width: 1024,
+            height: 512,
+            base: 0xFF000A0F,
+            palette: [0xFF101838, 0xFF4A1E5C, 0xFF2E7A8C],
+            milky_way: 0.35,
+            nebula: 0.45,
+        }
+    }
+}
+
+/// Fondo del cielo como textura equirectangular: se genera (o carga) una vez y
+/// cada frame se muestrea por la dirección de vista de cada píxel
+pub struct SkyBackground {
+    pub width: usize,
+    pub height: usize,
+    pub texels: Vec<u32>, // 0xAARRGGBB, fila 0 = polo norte (+Y)
+}
+
+impl SkyBackground {
+    /// Genera la Vía Láctea y las nebulosas. Con la misma configuración el
+    /// resultado es siempre el mismo
+    pub fn generate(settings: &NebulaSettings) -> Self {
+        let seed = (settings.seed ^ (settings.seed >> 32)) as u32;
+        // Polo norte y centro galácticos en las mismas coordenadas que el catálogo de estrellas
+        let galactic_pole = Star::from_equatorial(12.857, 27.13, 0.0, 0.0).direction;
+        let galactic_center = Star::from_equatorial(17.761, -28.94, 0.0, 0.0).direction;
+        let base = unpack(settings.base);
+        let palette = settings.palette.map(unpack);
+
+        let mut texels = Vec::with_capacity(settings.width * settings.height);
+        for y in 0..settings.height {
+            for x in 0..settings.width {
+                let dir = texel_direction(x, y, settings.width, settings.height);
+
+                // Vía Láctea: banda gaussiana alrededor del plano galáctico, con
+                // estructura de ruido, una franja de polvo oscura y un bulbo en el centro
+                let latitude = dir.dot(&galactic_pole);
+                let band = (-(latitude / 0.18).powi(2)).exp();
+                let dust = 1.0 - 0.6 * (-(latitude / 0.03).powi(2)).exp() * fbm(dir * 6.0, 4, seed ^ 0x5151);
+                let bulge = 1.0 + 1.5 * smooth_step((dir.dot(&galactic_center) - 0.6) / 0.4);
+                let glow = settings.milky_way * band * dust * bulge * (0.5 + fbm(dir * 4.0, 5, seed));
+
+                // Nebulosas: nubes de ruido recortadas con un umbral
+                let cloud = smooth_step((fbm(dir * 2.5, 5, seed ^ 0xA5A5) - 0.45) / 0.35);
+                let tint = fbm(dir * 1.5, 3, seed ^ 0x0F0F);
+                let nebula_color = gradient(&palette, tint);
+                let nebula = settings.nebula * cloud;
+
+                let milky_color = [0.85, 0.82, 0.78];
+                let mut color = [0.0; 3];
+                for c in 0..3 {
+                    color[c] = base[c] + nebula_color[c] * nebula + milky_color[c] * glow;
+                }
+                texels.push(pack(color));
+            }
+        }
+
+        Self { width: settings.width, height: settings.height, texels }
+    }
+
+    /// Imagen PPM (P6 binaria o P3 en texto) con proyección equirectangular
+    pub fn from_ppm(bytes: &[u8]) -> Result<Self, String> {
+        // Cabecera: formato, ancho, alto y valor máximo, con comentarios `#`
+        let mut fields = Vec::new();
+        let mut i = 0;
+        while fields.len() < 4 {
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i < bytes.len() && bytes[i] == b'#' {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            let start = i;
+            while i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if start == i {
+                return Err("cabecera PPM incompleta".to_string());
+            }
+            fields.push(String::from_utf8_lossy(&bytes[start..i]).to_string());
+        }
+        let num = |s: &str| s.parse::<usize>().map_err(|_| format!("número inválido '{}' en la cabecera", s));
+        let (width, height, max) = (num(&fields[1])?, num(&fields[2])?, num(&fields[3])?);
+        if width == 0 || height == 0 || max == 0 || max > 255 {
+            return Err("solo se admiten PPM de 8 bits no vacíos".to_string());
+        }
+
+        let values: Vec<u8> = match fields[0].as_str() {
+            "P6" => bytes.get(i + 1..).unwrap_or(&[]).to_vec(),
+            "P3" => String::from_utf8_lossy(&bytes[i..])
+                .split_whitespace()
+                .map(|v| v.parse::<u8>().map_err(|_| format!("valor inválido '{}'", v)))
+                .collect::<Result<_, _>>()?,
+            other => return Err(format!("formato '{}' no soportado (se espera P3 o P6)", other)),
+        };
+        if values.len() < width * height * 3 {
+            return Err("faltan píxeles en el PPM".to_string());
+        }
+
+        let scale = 255.0 / max as f32;
+        let texels = values
+            .chunks_exact(3)
+            .take(width * height)
+            .map(|rgb| pack([rgb[0] as f32 * scale / 255.0, rgb[1] as f32 * scale / 255.0, rgb[2] as f32 * scale / 255.0]))
+            .collect();
+        Ok(Self { width, height, texels })
+    }
+
+    pub fn load_ppm(file: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(file).map_err(|e| e.to_string())?;
+        Self::from_ppm(&bytes)
+    }
+
+    /// Color en una dirección (interpolación bilineal, el ancho da la vuelta)
+    pub fn sample(&self, direction: Vec3) -> u32 {
+        let d = direction.normalize();
+        let u = 0.5 + d.z.atan2(d.x) / std::f32::consts::TAU;
+        let v = 0.5 - d.y.clamp(-1.0, 1.0).asin() / std::f32::consts::PI;
+
+        let x = u * self.width as f32 - 0.5;
+        let y = (v * self.height as f32 - 0.5).clamp(0.0, self.height as f32 - 1.0);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let texel = |tx: f32, ty: f32| {
+            let tx = (tx as i64).rem_euclid(self.width as i64) as usize;
+            let ty = (ty as usize).min(self.height - 1);
+            unpack(self.texels[ty * self.width + tx])
+        };
+        let (c00, c10, c01, c11) = (texel(x0, y0), texel(x0 + 1.0, y0), texel(x0, y0 + 1.0), texel(x0 + 1.0, y0 + 1.0));
+
+        let mut color = [0.0; 3];
+        for c in 0..3 {
+            let top = c00[c] + (c10[c] - c00[c]) * fx;
+            let bottom = c01[c] + (c11[c] - c01[c]) * fx;
+            color[c] = top + (bottom - top) * fy;
+        }
+        pack(color)
+    }
+
+    /// Pinta todo el framebuffer con el fondo. `view_proj` usa la vista solo-rotación
+    pub fn render(&self, fb: &mut Framebuffer, view_proj: &Mat4) {
+        let Some(inv) = view_proj.inverse() else {
+            return;
+        };
+        let (w, h) = (fb.width as f32, fb.height as f32);
+        // Con la cámara en el origen, el punto desproyectado ya es la dirección;
+        // es afín en el píxel, así que alcanza con tres esquinas
+        let ray = |x: f32, y: f32| screen_to_world(x, y, 0.5, &inv, w, h);
+        let (Some(origin), Some(right), Some(down)) = (ray(0.5, 0.5), ray(1.5, 0.5), ray(0.5, 1.5)) else {
+            return;
+        };
+        let (step_x, step_y) = (right - origin, down - origin);
+
+        for y in 0..fb.height {
+            let row = origin + step_y * y as f32;
+            for x in 0..fb.width {
+                fb.buffer[y * fb.width + x] = self.sample(row + step_x * x as f32);
+            }
+        }
+    }
+}
+
+/// Dirección del centro del texel (x, y) de una textura equirectangular
+fn texel_direction(x: usize, y: usize, width: usize, height: usize) -> Vec3 {
+    let lon = ((x as f32 + 0.5) / width as f32 - 0.5) * std::f32::consts::TAU;
+    let lat = (0.5 - (y as f32 + 0.5) / height as f32) * std::f32::consts::PI;
+    Vec3::new(lat.cos() * lon.cos(), lat.sin(), lat.cos() * lon.sin())
+}
+
+fn gradient(palette: &[[f32; 3]; 3], t: f32) -> [f32; 3] {
+    let t = t.clamp(0.0, 1.0) * 2.0;
+    let (a, b, f) = if t < 1.0 { (palette[0], palette[1], t) } else { (palette[1], palette[2], t - 1.0) };
+    [a[0] + (b[0] - a[0]) * f, a[1] + (b[1] - a[1]) * f, a[2] + (b[2] - a[2]) * f]
+}
+
+/// Valor pseudoaleatorio en [0, 1] para un punto de la grilla
+fn hash(x: i32, y: i32, z: i32, seed: u32) -> f32 {
+    let mut h = seed
+        ^ (x as u32).wrapping_mul(0x8DA6_B343)
+        ^ (y as u32).wrapping_mul(0xD816_3841)
+        ^ (z as u32).wrapping_mul(0xCB1A_B31F);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0x5BD1_E995);
+    h ^= h >> 15;
+    (h & 0x00FF_FFFF) as f32 / 0x00FF_FFFF as f32
+}
+
+/// Ruido de valor 3D con interpolación suave. Se evalúa sobre la dirección,
+/// así la textura no tiene costura en los bordes ni en los polos
+fn value_noise(p: Vec3, seed: u32) -> f32 {
+    let (fx, fy, fz) = (p.x.floor(), p.y.floor(), p.z.floor());
+    let (ix, iy, iz) = (fx as i32, fy as i32, fz as i32);
+    let (tx, ty, tz) = (smooth_step(p.x - fx), smooth_step(p.y - fy), smooth_step(p.z - fz));
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let corner = |dx: i32, dy: i32, dz: i32| hash(ix + dx, iy + dy, iz + dz, seed);
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), tx);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), tx);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), tx);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), tx);
+    lerp(lerp(x00, x10, ty), lerp(x01, x11, ty), tz)
+}
+
+/// Suma de octavas de ruido, normalizada a [0, 1]
+fn fbm(p: Vec3, octaves: u32, seed: u32) -> f32 {
+    let (mut sum, mut amplitude, mut total, mut frequency) = (0.0, 0.5, 0.0, 1.0);
+    for octave in 0..octaves {
+        sum += amplitude * value_noise(p * frequency, seed.wrapping_add(octave.wrapping_mul(0x9E37_79B9)));
+        total += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    sum / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::DepthMode;
+
+    fn small(seed: u64) -> NebulaSettings {
+        NebulaSettings { seed, width: 64, height: 32, ..Default::default() }
+    }
+
+    fn luminance(color: u32) -> f32 {
+        unpack(color).iter().sum::<f32>() / 3.0
+    }
+
+    #[test]
+    fn test_generation_is_seeded() {
+        let a = SkyBackground::generate(&small(3));
+        assert_eq!(a.texels, SkyBackground::generate(&small(3)).texels);
+        assert_ne!(a.texels, SkyBackground::generate(&small(4)).texels);
+    }
+
+    #[test]
+    fn test_milky_way_is_brighter_than_galactic_pole() {
+        let settings = NebulaSettings { nebula: 0.0, ..small(1) };
+        let sky = SkyBackground::generate(&settings);
+        let center = Star::from_equatorial(17.761, -28.94, 0.0, 0.0).direction;
+        let pole = Star::from_equatorial(12.857, 27.13, 0.0, 0.0).direction;
+        assert!(luminance(sky.sample(center)) > luminance(sky.sample(pole)) + 0.1);
+    }
+
+    #[test]
+    fn test_ppm_loading_and_equirect_mapping() {
+        // 4x2: fila de arriba roja (norte), fila de abajo azul (sur)
+        let mut bytes = b"P6\n# fondo de prueba\n4 2\n255\n".to_vec();
+        for row in [[255u8, 0, 0], [0, 0, 255]] {
+            for _ in 0..4 {
+                bytes.extend_from_slice(&row);
+            }
+        }
+        let sky = SkyBackground::from_ppm(&bytes).unwrap();
+        assert_eq!((sky.width, sky.height), (4, 2));
+        assert_eq!(sky.sample(Vec3::unit_y()), 0xFFFF0000);
+        assert_eq!(sky.sample(-Vec3::unit_y()), 0xFF0000FF);
+
+        let text = SkyBackground::from_ppm(b"P3 2 1 15  15 15 15  0 0 0").unwrap();
+        assert_eq!(text.texels, vec![0xFFFFFFFF, 0xFF000000]);
+
+        assert!(SkyBackground::from_ppm(b"P5 2 1 255 ab").is_err());
+        assert!(SkyBackground::from_ppm(b"P6 2 2 255 abc").is_err());
+    }
+
+    #[test]
+    fn test_render_samples_view_direction() {
+        let sky = SkyBackground::generate(&small(9));
+        let forward = Vec3::new(0.3, 0.2, 1.0).normalize();
+        let view = Mat4::look_at(Vec3::zero(), forward, Vec3::unit_y());
+        let view_proj = Mat4::perspective_reversed_z(1.0, 2.0, 0.1, 1000.0) * view;
+
+        let mut fb = Framebuffer::new(64, 32);
+        fb.set_depth_mode(DepthMode::ReversedZ);
+        sky.render(&mut fb, &view_proj);
+
+        // El centro de la pantalla es la dirección de vista
+        let center = fb.get_pixel(32, 16).unwrap();
+        let expected = sky.sample(forward);
+        for (a, b) in unpack(center).iter().zip(unpack(expected).iter()) {
+            assert!((a - b).abs() < 0.05);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::framebuffer::{Framebuffer, pack, unpack};
+
+/// Bloom: los píxeles más brillantes que `threshold` (luminancia más emisión)
+/// se reducen, se difuminan y se suman de vuelta sobre el frame
+pub struct Bloom {
+    pub threshold: f32,
+    pub intensity: f32,
+    pub downsample: usize, // factor de reducción de la imagen de brillo
+    pub radius: usize,     // radio del blur en píxeles reducidos
+    pub passes: usize,     // pasadas de blur de caja (tres se parecen a una gaussiana)
+    width: usize,
+    height: usize,
+    bright: Vec<[f32; 3]>,
+    scratch: Vec<[f32; 3]>,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self {
+            threshold: 0.9,
+            intensity: 0.8,
+            downsample: 4,
+            radius: 3,
+            passes: 3,
+            width: 0,
+            height: 0,
+            bright: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl Bloom {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aplica el bloom sobre el frame terminado
+    pub fn apply(&mut self, fb: &mut Framebuffer) {
+        let d = self.downsample.max(1);
+        self.width = fb.width.div_ceil(d);
+        self.height = fb.height.div_ceil(d);
+        self.bright.clear();
+        self.bright.resize(self.width * self.height, [0.0; 3]);
+
+        // Extracción + reducción: promedio de lo que pasa el umbral en cada bloque
+        let mut any = false;
+        for y in 0..fb.height {
+            for x in 0..fb.width {
+                let index = y * fb.width + x;
+                let rgb = unpack(fb.buffer[index]);
+                let excess = luminance(rgb) + fb.emission[index] - self.threshold;
+                if excess > 0.0 {
+                    let cell = &mut self.bright[(y / d) * self.width + x / d];
+                    for c in 0..3 {
+                        cell[c] += rgb[c] * excess / (d * d) as f32;
+                    }
+                    any = true;
+                }
+            }
+        }
+        if !any {
+            return;
+        }
+
+        for _ in 0..self.passes {
+            self.blur(true);
+            self.blur(false);
+        }
+
+        // Composición: la imagen reducida se amplía con filtrado bilineal
+        for y in 0..fb.height {
+            for x in 0..fb.width {
+                let glow = self.sample((x as f32 + 0.5) / d as f32 - 0.5, (y as f32 + 0.5) / d as f32 - 0.5);
+                if glow.iter().all(|&c| c <= 0.0) {
+                    continue;
+                }
+                let index = y * fb.width + x;
+                let mut rgb = unpack(fb.buffer[index]);
+                for c in 0..3 {
+                    rgb[c] += glow[c] * self.intensity;
+                }
+                fb.buffer[index] = pack(rgb);
+            }
+        }
+    }
+
+    /// Blur de caja separable en una dirección
+    fn blur(&mut self, horizontal: bool) {
+        let (w, h, r) = (self.width as i32, self.height as i32, self.radius as i32);
+        let norm = 1.0 / (2 * r + 1) as f32;
+        self.scratch.clear();
+        self.scratch.resize(self.bright.len(), [0.0; 3]);
+
+        for y in 0..h {
+            for x in 0..w {
+                let mut sum = [0.0; 3];
+                for k in -r..=r {
+                    let (sx, sy) = if horizontal { (x + k, y) } else { (x, y + k) };
+                    if sx < 0 || sy < 0 || sx >= w || sy >= h {
+                        continue;
+                    }
+                    let texel = self.bright[(sy * w + sx) as usize];
+                    for c in 0..3 {
+                        sum[c] += texel[c];
+                    }
+                }
+                self.scratch[(y * w + x) as usize] = sum.map(|c| c * norm);
+            }
+        }
+        std::mem::swap(&mut self.bright, &mut self.scratch);
+    }
+
+    fn sample(&self, x: f32, y: f32) -> [f32; 3] {
+        let x = x.clamp(0.0, (self.width - 1) as f32);
+        let y = y.clamp(0.0, (self.height - 1) as f32);
+        let (x0, y0) = (x as usize, y as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+        let texel = |x: usize, y: usize| self.bright[y * self.width + x];
+        let (a, b, c, e) = (texel(x0, y0), texel(x1, y0), texel(x0, y1), texel(x1, y1));
+        std::array::from_fn(|i| {
+            let top = a[i] + (b[i] - a[i]) * fx;
+            let bottom = c[i] + (e[i] - c[i]) * fx;
+            top + (bottom - top) * fy
+        })
+    }
+}
+
+fn luminance(rgb: [f32; 3]) -> f32 {
+    0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::pipeline::black_frame;
+
+    fn frame_with_block(emission: f32) -> Framebuffer {
+        let (mut fb, _) = black_frame(96, 96);
+        fb.set_emission_level(emission);
+        for y in 46..50 {
+            for x in 46..50 {
+                fb.set_pixel_with_depth(x, y, 0xFFFFDD44, 0.5);
+            }
+        }
+        fb.set_emission_level(0.0);
+        fb
+    }
+
+    #[test]
+    fn test_emissive_pixels_bleed_into_neighbors() {
+        let mut fb = frame_with_block(2.5);
+        Bloom::new().apply(&mut fb);
+
+        let near = fb.get_pixel(56, 48).unwrap();
+        assert_ne!(near, 0xFF000000);
+        assert!((near >> 16) & 0xFF > near & 0xFF); // brillo del color del sol
+        assert_eq!(fb.get_pixel(0, 0), Some(0xFF000000));
+    }
+
+    #[test]
+    fn test_dim_frame_is_untouched() {
+        let mut fb = frame_with_block(0.0);
+        fb.buffer.iter_mut().filter(|c| **c != 0xFF000000).for_each(|c| *c = 0xFF806040);
+        let before = fb.buffer.clone();
+        Bloom::new().apply(&mut fb);
+        assert_eq!(fb.buffer, before);
+    }
+}
@@ -1,5 +1,9 @@
+pub mod atmosphere;
+pub mod background;
+pub mod bloom;
 pub mod skybox;
+pub mod sun;
 pub mod warp;
+pub mod warp_visuals;
 
-pub use skybox::*;
-pub use warp::*;
+pub use atmosphere::*;
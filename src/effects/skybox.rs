@@ -1,69 +1,382 @@
-use crate::math::{Vec3, Mat4};
-use crate::framebuffer::Framebuffer;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use crate::math::{Vec3, Vec4, Mat4};
+use crate::framebuffer::{Framebuffer, lerp_color};
+
+const LIMITING_MAGNITUDE: f32 = 6.5;       // las más débiles que se ven a simple vista
+const BRIGHT_MAGNITUDE: f32 = 0.0;         // desde acá el brillo ya es máximo
+const MIN_BRIGHTNESS: f32 = 0.15;
+const CONSTELLATION_COLOR: u32 = 0xFF2A4058;
+
+/// Estrella del fondo: dirección desde la cámara, magnitud aparente y color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Star {
+    pub direction: Vec3, // unitaria
+    pub magnitude: f32,  // menor = más brillante
+    pub color: u32,
+}
+
+impl Star {
+    /// Estrella de catálogo: ascensión recta en horas, declinación en grados e índice B-V.
+    /// El polo norte celeste es +Y y la ascensión recta 0h apunta a +X
+    pub fn from_equatorial(ra_hours: f32, dec_deg: f32, magnitude: f32, bv: f32) -> Self {
+        let ra = (ra_hours * 15.0).to_radians();
+        let dec = dec_deg.to_radians();
+        Self {
+            direction: Vec3::new(dec.cos() * ra.cos(), dec.sin(), dec.cos() * ra.sin()),
+            magnitude,
+            color: bv_to_color(bv),
+        }
+    }
+
+    /// Brillo relativo en [MIN_BRIGHTNESS, 1] según la magnitud
+    pub fn brightness(&self) -> f32 {
+        ((LIMITING_MAGNITUDE - self.magnitude) / (LIMITING_MAGNITUDE - BRIGHT_MAGNITUDE)).clamp(MIN_BRIGHTNESS, 1.0)
+    }
+
+    /// Radio en píxeles del punto que la dibuja (puede ser menor a un píxel)
+    pub fn radius(&self) -> f32 {
+        0.3 + 1.2 * self.brightness()
+    }
+}
+
+/// Color aproximado de una estrella según su índice B-V (de azul a rojo, O a M)
+pub fn bv_to_color(bv: f32) -> u32 {
+    const TABLE: [(f32, u32); 7] = [
+        (-0.40, 0xFF9BB0FF),
+        (0.00, 0xFFCAD7FF),
+        (0.40, 0xFFF8F7FF),
+        (0.60, 0xFFFFF4EA),
+        (0.80, 0xFFFFE2B8),
+        (1.40, 0xFFFFC07A),
+        (2.00, 0xFFFF9A5A),
+    ];
+    let bv = bv.clamp(TABLE[0].0, TABLE[TABLE.len() - 1].0);
+    for pair in TABLE.windows(2) {
+        let ((b0, c0), (b1, c1)) = (pair[0], pair[1]);
+        if bv <= b1 {
+            return lerp_color(c0, c1, (bv - b0) / (b1 - b0));
+        }
+    }
+    TABLE[TABLE.len() - 1].1
+}
 
 pub struct Skybox {
-    stars: Vec<Vec3>,
+    stars: Vec<Star>,
+    names: Vec<Option<String>>,           // nombre de catálogo de cada estrella
+    constellations: Vec<(usize, usize)>,  // segmentos entre índices de `stars`
+    pub show_constellations: bool,
+    pub twinkle: f32,                     // variación máxima de brillo (0 = quietas)
 }
 
 impl Skybox {
-    pub fn new(count: usize) -> Self {
-        let mut stars = Vec::new();
+    /// Cielo aleatorio reproducible: la misma semilla da siempre las mismas estrellas
+    pub fn new(count: usize, seed: u64) -> Self {
+        let mut skybox = Self::empty();
+        skybox.add_random_stars(count, seed);
+        skybox
+    }
+
+    fn empty() -> Self {
+        Self {
+            stars: Vec::new(),
+            names: Vec::new(),
+            constellations: Vec::new(),
+            show_constellations: true,
+            twinkle: 0.25,
+        }
+    }
+
+    /// Agrega estrellas débiles al azar (relleno detrás de un catálogo)
+    pub fn add_random_stars(&mut self, count: usize, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
 
         for _ in 0..count {
-            // vector aleatorio normalizado
-            let x = rand_range(-1.0, 1.0);
-            let y = rand_range(-1.0, 1.0);
-            let z = rand_range(-1.0, 1.0);
-            let v = Vec3::new(x, y, z).normalize() * 300.0; // esfera grande
+            // vector aleatorio normalizado (descartando los de fuera de la esfera
+            // para que la distribución sea uniforme)
+            let direction = loop {
+                let v = Vec3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+                let len2 = v.length_squared();
+                if len2 > 1e-6 && len2 <= 1.0 {
+                    break v.normalize();
+                }
+            };
+            // Hay muchas más estrellas débiles que brillantes
+            let u: f32 = rng.gen_range(0.0..1.0);
+            let magnitude = LIMITING_MAGNITUDE - 4.0 * u * u * u;
+            let bv = rng.gen_range(-0.2..1.6);
 
-            stars.push(v);
+            self.stars.push(Star { direction, magnitude, color: bv_to_color(bv) });
+            self.names.push(None);
         }
+    }
 
-        Self { stars }
+    /// Catálogo CSV con cabecera `name,ra_hours,dec_deg,vmag,bv` (las líneas con # se ignoran)
+    pub fn from_catalog_text(text: &str) -> Result<Self, String> {
+        let mut skybox = Self::empty();
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("name,") {
+                continue;
+            }
+            let err = |what: &str| format!("línea {}: {}", n + 1, what);
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [name, ra, dec, mag, bv] = fields.as_slice() else {
+                return Err(err("se esperaban 5 columnas"));
+            };
+            let num = |s: &str| s.parse::<f32>().map_err(|_| err(&format!("número inválido '{}'", s)));
+
+            skybox.stars.push(Star::from_equatorial(num(ra)?, num(dec)?, num(mag)?, num(bv)?));
+            skybox.names.push(Some(name.to_string()));
+        }
+        Ok(skybox)
+    }
+
+    pub fn load_catalog(file: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(file).map_err(|e| e.to_string())?;
+        Self::from_catalog_text(&text)
+    }
+
+    /// Líneas de constelaciones: `Nombre: estrella estrella ...` une las estrellas
+    /// del catálogo en una polilínea
+    pub fn parse_constellations(&mut self, text: &str) -> Result<(), String> {
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |what: &str| format!("línea {}: {}", n + 1, what);
+            let Some((_, list)) = line.split_once(':') else {
+                return Err(err("falta ':' después del nombre"));
+            };
+            let indices = list
+                .split_whitespace()
+                .map(|name| self.find_star(name).ok_or_else(|| err(&format!("estrella desconocida '{}'", name))))
+                .collect::<Result<Vec<usize>, String>>()?;
+            self.constellations.extend(indices.windows(2).map(|w| (w[0], w[1])));
+        }
+        Ok(())
+    }
+
+    pub fn load_constellations(&mut self, file: &str) -> Result<(), String> {
+        let text = std::fs::read_to_string(file).map_err(|e| e.to_string())?;
+        self.parse_constellations(&text)
+    }
+
+    /// Índice de la estrella de catálogo con ese nombre
+    pub fn find_star(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+    }
+
+    pub fn stars(&self) -> &[Star] {
+        &self.stars
     }
 
-    pub fn render(&self, fb: &mut Framebuffer, view: Mat4, projection: Mat4) {
-        // Remover traslación de la vista (para que las estrellas no se muevan)
+    /// Dibuja el cielo. `view` debe ser solo rotación (se le quita la traslación
+    /// por las dudas) y `time` en segundos anima el titileo
+    pub fn render(&self, fb: &mut Framebuffer, view: Mat4, projection: Mat4, time: f32) {
         let mut pure_view = view;
         pure_view.m[0][3] = 0.0;
         pure_view.m[1][3] = 0.0;
         pure_view.m[2][3] = 0.0;
+        let view_proj = projection * pure_view;
+        let far = fb.depth_mode.far_value();
 
-        for star in &self.stars {
-            let pos = *star;
-
-            // MVP
-            let clip = projection * pure_view * Mat4::translation(pos.x, pos.y, pos.z);
-
-            // convertir centro de la estrella
-            let p = clip.mul_point(Vec3::new(0.0, 0.0, 0.0));
-
-            // ignorar si está detrás de la cámara
-            if p.z < 0.0 {
+        for (i, star) in self.stars.iter().enumerate() {
+            let Some((x, y)) = project_direction(star.direction, &view_proj, fb) else {
                 continue;
+            };
+            let brightness = star.brightness() * self.twinkle_factor(i, time);
+            splat(fb, x, y, star.radius(), star.color, brightness, far);
+        }
+
+        if self.show_constellations {
+            for &(a, b) in &self.constellations {
+                let pa = project_direction(self.stars[a].direction, &view_proj, fb);
+                let pb = project_direction(self.stars[b].direction, &view_proj, fb);
+                if let (Some((x0, y0)), Some((x1, y1))) = (pa, pb) {
+                    fb.draw_line(x0 as i32, y0 as i32, far, x1 as i32, y1 as i32, far, CONSTELLATION_COLOR);
+                }
             }
+        }
+    }
 
-            // convertir a pantalla
-            let sx = ((p.x + 1.0) * 0.5 * fb.width as f32) as i32;
-            let sy = ((1.0 - p.y) * 0.5 * fb.height as f32) as i32;
+    /// Variación de brillo de la estrella `i`: cada una con su fase y frecuencia
+    fn twinkle_factor(&self, i: usize, time: f32) -> f32 {
+        if self.twinkle <= 0.0 {
+            return 1.0;
+        }
+        let h = (i as u32).wrapping_mul(2_654_435_761);
+        let phase = (h & 0xFFFF) as f32 / 65535.0 * std::f32::consts::TAU;
+        let frequency = 1.5 + ((h >> 16) & 0xFF) as f32 / 255.0 * 3.0;
+        1.0 - self.twinkle * (0.5 + 0.5 * (time * frequency + phase).sin())
+    }
+}
 
-            let size = 2; // 2x2 píxeles
+/// Proyecta una dirección (punto en el infinito, w = 0) a píxeles.
+/// Las que quedan detrás de la cámara (w de clip <= 0) se descartan
+fn project_direction(direction: Vec3, view_proj: &Mat4, fb: &Framebuffer) -> Option<(f32, f32)> {
+    let clip = view_proj.mul_vec4(Vec4::from_direction(direction));
+    if clip.w <= 1e-6 {
+        return None;
+    }
+    let (nx, ny) = (clip.x / clip.w, clip.y / clip.w);
+    Some(((nx + 1.0) * 0.5 * fb.width as f32, (1.0 - ny) * 0.5 * fb.height as f32))
+}
 
-for dy in 0..size {
-    for dx in 0..size {
-        let px = sx + dx;
-        let py = sy + dy;
+/// Punto con antialiasing: cada píxel se cubre según su distancia al centro
+/// (subpíxel) y se mezcla con el fondo. Solo pinta donde no hay geometría
+fn splat(fb: &mut Framebuffer, x: f32, y: f32, radius: f32, color: u32, brightness: f32, far: f32) {
+    let reach = radius + 0.5;
+    let (x0, x1) = ((x - reach).floor().max(0.0) as i32, (x + reach).ceil().min(fb.width as f32 - 1.0) as i32);
+    let (y0, y1) = ((y - reach).floor().max(0.0) as i32, (y + reach).ceil().min(fb.height as f32 - 1.0) as i32);
 
-        if px >= 0 && py >= 0 && px < fb.width as i32 && py < fb.height as i32 {
-            fb.set_pixel(px as usize, py as usize, 0xFFFFFFFF);
+    for py in y0..=y1 {
+        for px in x0..=x1 {
+            // centro del píxel en (px + 0.5, py + 0.5)
+            let d = ((px as f32 + 0.5 - x).powi(2) + (py as f32 + 0.5 - y).powi(2)).sqrt();
+            let coverage = (radius + 0.5 - d).clamp(0.0, 1.0);
+            let (ux, uy) = (px as usize, py as usize);
+            if coverage <= 0.0 || !fb.depth_test(ux, uy, far) {
+                continue;
+            }
+            let background = fb.get_pixel(ux, uy).unwrap_or(0);
+            fb.set_pixel(ux, uy, lerp_color(background, color, brightness * coverage));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::pipeline::black_frame;
+
+    /// Cielo con estrellas blancas de magnitud 0 en esas direcciones, visto desde el origen hacia +Z
+    fn render_sky(directions: &[Vec3], time: f32, twinkle: f32, fb: &mut Framebuffer) {
+        let mut sky = Skybox::empty();
+        sky.twinkle = twinkle;
+        for &d in directions {
+            sky.stars.push(Star { direction: d.normalize(), magnitude: 0.0, color: 0xFFFFFFFF });
+            sky.names.push(None);
         }
+        let view = Mat4::look_at(Vec3::zero(), Vec3::unit_z(), Vec3::unit_y());
+        let projection = Mat4::perspective_reversed_z(1.0, 16.0 / 9.0, 0.1, 1000.0);
+        sky.render(fb, view, projection, time);
     }
-}
 
-fn rand_range(min: f32, max: f32) -> f32 {
-    let r = rand::random::<f32>();
-    min + r * (max - min)
+    fn lit(fb: &Framebuffer) -> usize {
+        fb.buffer.iter().filter(|&&c| c != 0xFF000000).count()
+    }
+
+    /// Direcciones repartidas sobre toda la esfera (espiral de Fibonacci)
+    fn sphere_directions(n: usize) -> Vec<Vec3> {
+        (0..n)
+            .map(|i| {
+                let y = 1.0 - 2.0 * (i as f32 + 0.5) / n as f32;
+                let r = (1.0 - y * y).sqrt();
+                let a = i as f32 * 2.399_963;
+                Vec3::new(r * a.cos(), y, r * a.sin())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_star_behind_camera_is_drawn() {
+        let (behind, ahead): (Vec<Vec3>, Vec<Vec3>) = sphere_directions(4000).into_iter().partition(|d| d.z <= 0.0);
+
+        let (mut fb, _) = black_frame(160, 90);
+        render_sky(&behind, 0.0, 0.0, &mut fb);
+        assert_eq!(lit(&fb), 0);
+
+        let (mut fb, _) = black_frame(160, 90);
+        render_sky(&ahead, 0.0, 0.0, &mut fb);
+        assert!(lit(&fb) > 0);
+    }
+
+    #[test]
+    fn test_splat_is_antialiased_and_respects_depth() {
+        // Estrella apenas corrida del centro: cae entre píxeles
+        let (mut fb, _) = black_frame(160, 90);
+        render_sky(&[Vec3::new(0.003, 0.002, 1.0)], 0.0, 0.0, &mut fb);
+        let values: Vec<u32> = fb.buffer.iter().map(|c| c & 0xFF).filter(|&v| v > 0).collect();
+        assert!(values.len() > 4);
+        assert!(values.contains(&255));
+        assert!(values.iter().any(|&v| v < 255)); // bordes parcialmente cubiertos
+
+        // Con geometría delante no se dibuja nada
+        let (mut fb, _) = black_frame(160, 90);
+        fb.zbuffer.fill(0.5);
+        render_sky(&[Vec3::unit_z()], 0.0, 0.0, &mut fb);
+        assert_eq!(lit(&fb), 0);
+    }
+
+    #[test]
+    fn test_twinkle_changes_brightness_over_time() {
+        let stars = sphere_directions(400);
+        let frame = |time: f32, twinkle: f32| {
+            let (mut fb, _) = black_frame(160, 90);
+            render_sky(&stars, time, twinkle, &mut fb);
+            fb.buffer
+        };
+        assert!(frame(0.0, 0.0) == frame(1.3, 0.0));
+        assert!(frame(0.0, 0.5) != frame(1.3, 0.5));
+    }
+
+    const CATALOG: &str = "name,ra_hours,dec_deg,vmag,bv\n\
+                           # comentario\n\
+                           Polaris,0.0,90.0,1.98,0.60\n\
+                           Rigel,6.0,0.0,0.13,-0.03\n\
+                           Betelgeuse,0.0,0.0,0.50,1.85\n";
+
+    #[test]
+    fn test_same_seed_same_sky() {
+        let a = Skybox::new(200, 7);
+        assert_eq!(a.stars(), Skybox::new(200, 7).stars());
+        assert_ne!(a.stars(), Skybox::new(200, 8).stars());
+        assert!(a.stars().iter().all(|s| (s.direction.length() - 1.0).abs() < 1e-5));
+    }
+
+    #[test]
+    fn test_catalog_coordinates() {
+        let sky = Skybox::from_catalog_text(CATALOG).unwrap();
+        assert_eq!(sky.stars().len(), 3);
+        let dir = |name: &str| sky.stars()[sky.find_star(name).unwrap()].direction;
+        assert!((dir("Polaris") - Vec3::unit_y()).length() < 1e-5);
+        assert!((dir("Rigel") - Vec3::unit_z()).length() < 1e-5);
+        assert!((dir("betelgeuse") - Vec3::unit_x()).length() < 1e-5);
+
+        assert!(Skybox::from_catalog_text("Vega,18.6,38.8,0.03").is_err());
+        assert!(Skybox::from_catalog_text("Vega,18.6,norte,0.03,0.0").is_err());
+    }
+
+    #[test]
+    fn test_magnitude_and_color() {
+        let sky = Skybox::from_catalog_text(CATALOG).unwrap();
+        let [polaris, rigel, betelgeuse] = [sky.stars()[0], sky.stars()[1], sky.stars()[2]];
+        assert!(rigel.brightness() > polaris.brightness());
+        assert!(rigel.radius() > polaris.radius());
+
+        // Rigel (B-V negativo) es azulada y Betelgeuse rojiza
+        let red_blue = |c: u32| (((c >> 16) & 0xFF) as i32, (c & 0xFF) as i32);
+        let (r_rigel, b_rigel) = red_blue(rigel.color);
+        let (r_betel, b_betel) = red_blue(betelgeuse.color);
+        assert!(b_rigel > r_rigel);
+        assert!(r_betel > b_betel);
+    }
+
+    #[test]
+    fn test_constellations_reference_catalog_stars() {
+        let mut sky = Skybox::from_catalog_text(CATALOG).unwrap();
+        sky.parse_constellations("# prueba\nTriángulo: Polaris Rigel Betelgeuse Polaris\n").unwrap();
+        assert_eq!(sky.constellations, [(0, 1), (1, 2), (2, 0)]);
+        assert!(sky.parse_constellations("Orion: Rigel Saiph").is_err());
+    }
+
+    #[test]
+    fn test_bundled_catalog_loads() {
+        let mut sky = Skybox::load_catalog("assets/stars/bright_stars.csv").unwrap();
+        sky.load_constellations("assets/stars/constellations.txt").unwrap();
+        assert!(sky.stars().len() > 50);
+        assert!(!sky.constellations.is_empty());
+    }
 }
@@ -0,0 +1,259 @@
+use crate::math::{Vec3, Mat4, world_to_screen};
+use crate::framebuffer::{Framebuffer, BlendMode};
+
+/// Disco proyectado de una esfera: centro y radio en píxeles, y z de su centro
+fn screen_disk(fb: &Framebuffer, view_proj: &Mat4, center: Vec3, radius: f32) -> Option<(f32, f32, f32, f32)> {
+    if center.length_squared() <= radius * radius {
+        return None;
+    }
+    let side = if center.x.abs() < center.length() * 0.9 { Vec3::unit_x() } else { Vec3::unit_y() };
+    let edge = center + center.cross(&side).normalize() * radius;
+
+    let (w, h) = (fb.width as f32, fb.height as f32);
+    let (cx, cy, z) = world_to_screen(center, view_proj, w, h)?;
+    let (ex, ey, _) = world_to_screen(edge, view_proj, w, h)?;
+    Some((cx, cy, ((ex - cx).powi(2) + (ey - cy).powi(2)).sqrt(), z))
+}
+
+/// Corona animada del sol: un billboard aditivo alrededor del disco con
+/// serpentinas que cambian lentamente. Respeta el z-buffer (los planetas la tapan)
+pub struct Corona {
+    pub color: u32,
+    pub extent: f32,      // alcance máximo en radios del sol
+    pub streamers: usize, // cantidad de serpentinas
+    pub intensity: f32,
+}
+
+impl Default for Corona {
+    fn default() -> Self {
+        Self {
+            color: 0xFFFFC866,
+            extent: 3.0,
+            streamers: 9,
+            intensity: 0.9,
+        }
+    }
+}
+
+impl Corona {
+    /// `center` es la posición del sol relativa a la cámara; `view_proj` sin traslación
+    pub fn render(&self, fb: &mut Framebuffer, view_proj: &Mat4, center: Vec3, radius: f32, time: f32) {
+        let Some((cx, cy, r, z)) = screen_disk(fb, view_proj, center, radius) else {
+            return;
+        };
+        let reach = r * self.extent;
+        let min_x = (cx - reach).floor().max(0.0) as usize;
+        let max_x = (cx + reach).ceil().min(fb.width as f32 - 1.0);
+        let min_y = (cy - reach).floor().max(0.0) as usize;
+        let max_y = (cy + reach).ceil().min(fb.height as f32 - 1.0);
+        if max_x < 0.0 || max_y < 0.0 {
+            return;
+        }
+
+        let n = self.streamers as f32;
+        for y in min_y..=max_y as usize {
+            for x in min_x..=max_x as usize {
+                let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+                let d = (dx * dx + dy * dy).sqrt() / r;
+                if d >= self.extent {
+                    continue;
+                }
+
+                // Serpentinas: el alcance varía con el ángulo y el tiempo
+                let angle = dy.atan2(dx);
+                let wave = ((angle * n + time * 0.3).sin() * (angle * (n * 0.5 + 1.0) - time * 0.5 + 1.7).sin()).abs();
+                let pulse = 1.0 + 0.08 * (time * 1.3).sin();
+                let falloff = ((self.extent - d) / (self.extent - 1.0)).clamp(0.0, 1.0);
+                let glow = falloff * falloff * (0.45 + 0.55 * wave) * pulse / d.max(1.0);
+
+                fb.blend_pixel_with_depth(x, y, self.color, self.intensity * glow, z, BlendMode::Additive);
+            }
+        }
+    }
+}
+
+/// Un reflejo del lente: disco suave sobre la recta sol-centro de pantalla
+#[derive(Debug, Clone, Copy)]
+pub struct FlareGhost {
+    pub offset: f32, // 0 = sobre el sol, 1 = centro de pantalla, 2 = reflejado
+    pub size: f32,   // radio en fracción del alto de la pantalla
+    pub color: u32,
+    pub alpha: f32,
+}
+
+/// Destellos de lente del sol. Solo aparecen si el sol está en pantalla y
+/// la parte visible de su disco (según el z-buffer) los atenúa
+pub struct LensFlare {
+    pub ghosts: Vec<FlareGhost>,
+    pub glare: f32, // intensidad de la estela horizontal sobre el sol
+}
+
+impl Default for LensFlare {
+    fn default() -> Self {
+        let ghost = |offset, size, color, alpha| FlareGhost { offset, size, color, alpha };
+        Self {
+            ghosts: vec![
+                ghost(0.45, 0.025, 0xFFFFE0A0, 0.35),
+                ghost(0.8, 0.06, 0xFF80C0FF, 0.15),
+                ghost(1.1, 0.015, 0xFFFFFFFF, 0.4),
+                ghost(1.4, 0.09, 0xFF90FF90, 0.1),
+                ghost(1.8, 0.04, 0xFFFF9060, 0.2),
+            ],
+            glare: 0.5,
+        }
+    }
+}
+
+impl LensFlare {
+    /// Fracción del disco del sol que se ve: se muestrea una grilla sobre el disco
+    /// y se compara con el z-buffer contra la profundidad del punto más cercano del sol
+    pub fn visibility(fb: &Framebuffer, view_proj: &Mat4, center: Vec3, radius: f32) -> f32 {
+        const GRID: i32 = 5;
+        let Some((cx, cy, r, _)) = screen_disk(fb, view_proj, center, radius) else {
+            return 0.0;
+        };
+        let front = center - center.normalize() * radius;
+        let Some((_, _, z_front)) = world_to_screen(front, view_proj, fb.width as f32, fb.height as f32) else {
+            return 0.0;
+        };
+
+        let (mut inside, mut visible) = (0, 0);
+        for j in 0..GRID {
+            for i in 0..GRID {
+                let u = (i as f32 + 0.5) / GRID as f32 * 2.0 - 1.0;
+                let v = (j as f32 + 0.5) / GRID as f32 * 2.0 - 1.0;
+                if u * u + v * v > 1.0 {
+                    continue;
+                }
+                inside += 1;
+
+                // Un poco hacia adentro para no muestrear el borde facetado
+                let (x, y) = (cx + u * r * 0.8, cy + v * r * 0.8);
+                if x < 0.0 || y < 0.0 || x >= fb.width as f32 || y >= fb.height as f32 {
+                    continue;
+                }
+                let stored = fb.zbuffer[y as usize * fb.width + x as usize];
+                if !fb.depth_mode.passes(stored, z_front) {
+                    visible += 1;
+                }
+            }
+        }
+        visible as f32 / inside as f32
+    }
+
+    /// Dibuja la estela y los reflejos sobre el frame terminado
+    pub fn render(&self, fb: &mut Framebuffer, view_proj: &Mat4, center: Vec3, radius: f32) {
+        let visibility = Self::visibility(fb, view_proj, center, radius);
+        if visibility <= 0.0 {
+            return;
+        }
+        let Some((sx, sy, r, _)) = screen_disk(fb, view_proj, center, radius) else {
+            return;
+        };
+        let (w, h) = (fb.width as f32, fb.height as f32);
+
+        // Estela horizontal a través del sol
+        let half_width = w * 0.35;
+        let half_height = (r * 0.15).max(1.5);
+        for y in (sy - half_height).max(0.0) as usize..=(sy + half_height).min(h - 1.0).max(0.0) as usize {
+            let fy = 1.0 - (y as f32 - sy).abs() / half_height;
+            for x in (sx - half_width).max(0.0) as usize..=(sx + half_width).min(w - 1.0).max(0.0) as usize {
+                let fx = 1.0 - (x as f32 - sx).abs() / half_width;
+                let alpha = self.glare * visibility * (fx * fx * fy).max(0.0);
+                fb.blend_pixel(x, y, 0xFFFFF0D0, alpha, BlendMode::Additive);
+            }
+        }
+
+        // Reflejos sobre la recta que une el sol con el centro de la pantalla
+        let (ax, ay) = (w * 0.5 - sx, h * 0.5 - sy);
+        for ghost in &self.ghosts {
+            let (gx, gy) = (sx + ax * ghost.offset, sy + ay * ghost.offset);
+            let gr = ghost.size * h;
+            for y in (gy - gr).max(0.0) as usize..=(gy + gr).min(h - 1.0).max(0.0) as usize {
+                for x in (gx - gr).max(0.0) as usize..=(gx + gr).min(w - 1.0).max(0.0) as usize {
+                    let d = ((x as f32 - gx).powi(2) + (y as f32 - gy).powi(2)).sqrt() / gr;
+                    if d < 1.0 {
+                        let alpha = ghost.alpha * visibility * (1.0 - d * d);
+                        fb.blend_pixel(x, y, ghost.color, alpha, BlendMode::Additive);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::generate_sphere;
+    use crate::renderer::pipeline::black_frame;
+    use crate::math::create_transform_matrix;
+
+    const SIZE: usize = 64;
+    const SUN: Vec3 = Vec3 { x: 0.0, y: 0.0, z: 40.0 };
+
+    fn scene(occluder: Option<(Vec3, f32)>) -> (Framebuffer, Mat4) {
+        let (mut fb, mut pipeline) = black_frame(SIZE, SIZE);
+
+        let view = Mat4::look_at(Vec3::zero(), Vec3::unit_z(), Vec3::unit_y());
+        let view_proj = pipeline.projection_matrix(1.0, 0.1, 1000.0) * view;
+        let sphere = generate_sphere(12, 12);
+        for (center, radius, color) in std::iter::once((SUN, 4.0, 0xFFFFDD44)).chain(occluder.map(|(c, r)| (c, r, 0xFF44AAFF))) {
+            let model = create_transform_matrix(center, Vec3::zero(), Vec3::new(radius, radius, radius));
+            pipeline.set_mvp(view_proj * model);
+            pipeline.set_color(color);
+            pipeline.draw_mesh(&mut fb, &sphere.vertices, &sphere.faces);
+        }
+        (fb, view_proj)
+    }
+
+    #[test]
+    fn test_visibility_uses_zbuffer() {
+        let (fb, view_proj) = scene(None);
+        assert_eq!(LensFlare::visibility(&fb, &view_proj, SUN, 4.0), 1.0);
+
+        // Un planeta delante tapa todo el disco
+        let (fb, view_proj) = scene(Some((Vec3::new(0.0, 0.0, 10.0), 3.0)));
+        assert_eq!(LensFlare::visibility(&fb, &view_proj, SUN, 4.0), 0.0);
+
+        // Tapado a medias
+        let (fb, view_proj) = scene(Some((Vec3::new(1.5, 0.0, 20.0), 2.0)));
+        let partial = LensFlare::visibility(&fb, &view_proj, SUN, 4.0);
+        assert!(partial > 0.0 && partial < 1.0, "{partial}");
+
+        // Detrás de la cámara
+        assert_eq!(LensFlare::visibility(&fb, &view_proj, -SUN, 4.0), 0.0);
+    }
+
+    #[test]
+    fn test_flare_only_when_sun_visible() {
+        let (mut fb, view_proj) = scene(None);
+        let before = fb.buffer.clone();
+        LensFlare::default().render(&mut fb, &view_proj, SUN, 4.0);
+        assert_ne!(fb.buffer, before);
+
+        let (mut fb, view_proj) = scene(Some((Vec3::new(0.0, 0.0, 10.0), 3.0)));
+        let before = fb.buffer.clone();
+        LensFlare::default().render(&mut fb, &view_proj, SUN, 4.0);
+        assert_eq!(fb.buffer, before);
+    }
+
+    #[test]
+    fn test_corona_surrounds_disk_and_is_occluded() {
+        let (mut fb, view_proj) = scene(None);
+        let (cx, cy, r, _) = screen_disk(&fb, &view_proj, SUN, 4.0).unwrap();
+        Corona::default().render(&mut fb, &view_proj, SUN, 4.0, 0.0);
+
+        // Justo afuera del disco hay corona; el disco queda igual
+        let halo = fb.get_pixel((cx + r * 1.3) as usize, cy as usize).unwrap();
+        assert_ne!(halo, 0xFF000000);
+        assert_eq!(fb.get_pixel(cx as usize, cy as usize), Some(0xFFFFDD44));
+
+        // Un planeta delante de la corona la tapa
+        let blocker = Vec3::new(SUN.x + 4.0 * 1.3 * 0.25, 0.0, 10.0);
+        let (mut fb, view_proj) = scene(Some((blocker, 1.0)));
+        let (bx, by, _) = world_to_screen(blocker, &view_proj, SIZE as f32, SIZE as f32).unwrap();
+        Corona::default().render(&mut fb, &view_proj, SUN, 4.0, 0.0);
+        assert_eq!(fb.get_pixel(bx as usize, by as usize), Some(0xFF44AAFF));
+    }
+}
@@ -1,46 +1,269 @@
-use crate::math::{Vec3, smooth_step};
+use crate::math::{DVec3, Vec3, smooth_step, catmull_rom};
+use crate::scene::solar_system::SolarSystem;
 
+/// Radios del destino a los que se detiene el warp
+pub const WARP_ARRIVAL_RADII: f64 = 5.0;
+/// Radios alrededor de cada cuerpo que la ruta no cruza
+pub const WARP_CLEARANCE_RADII: f64 = 2.0;
+
+const DETOUR_MARGIN: f64 = 1.5;  // el waypoint queda algo afuera de la esfera a esquivar
+const MAX_DETOURS: usize = 4;    // profundidad de la subdivisión de la ruta
+const SAMPLES_PER_SEGMENT: usize = 8;
+
+/// Viaje animado hacia un cuerpo: esquiva las esferas de los cuerpos en el camino
+/// y sigue al destino mientras se mueve
 pub struct WarpEffect {
     pub active: bool,
-    pub start_pos: Vec3,
-    pub end_pos: Vec3,
+    pub target: usize,     // índice como en `SolarSystem::body`
+    pub approach: DVec3,   // dirección desde el destino hacia el punto de llegada
+    pub start_pos: DVec3,
+    pub end_pos: DVec3,    // punto de llegada del último frame
+    pub direction: DVec3,  // dirección del último desplazamiento (unitaria)
     pub time: f32,
     pub duration: f32,
+    pub arrival_radii: f64,
+    pub clearance_radii: f64,
 }
 
 impl WarpEffect {
     pub fn new() -> Self {
         Self {
             active: false,
-            start_pos: Vec3::zero(),
-            end_pos: Vec3::zero(),
+            target: 0,
+            approach: DVec3::new(0.0, 0.0, 1.0),
+            start_pos: DVec3::zero(),
+            end_pos: DVec3::zero(),
+            direction: DVec3::new(0.0, 0.0, 1.0),
             time: 0.0,
             duration: 1.5,
+            arrival_radii: WARP_ARRIVAL_RADII,
+            clearance_radii: WARP_CLEARANCE_RADII,
         }
     }
 
-    pub fn start(&mut self, from: Vec3, to: Vec3) {
+    /// Empieza un warp hacia el cuerpo `target` (índice como en `SolarSystem::body`);
+    /// se llega por el lado de `from`
+    pub fn warp_to(&mut self, from: DVec3, target: usize, system: &SolarSystem) -> Result<(), String> {
+        let Some(body) = system.body(target) else {
+            return Err(format!("No existe el cuerpo {}", target));
+        };
+        let away = from - body.position();
+        self.approach = if away.length_squared() > 1e-12 { away.normalize() } else { DVec3::new(0.0, 0.0, 1.0) };
         self.active = true;
+        self.target = target;
         self.start_pos = from;
-        self.end_pos = to;
         self.time = 0.0;
+        if let Some(end) = self.arrival_point(system) {
+            self.end_pos = end;
+            if end.distance(&from) > 1e-9 {
+                self.direction = (end - from).normalize();
+            }
+        }
+        Ok(())
+    }
+
+    /// Avance del warp en [0, 1]
+    pub fn progress(&self) -> f32 {
+        (self.time / self.duration).clamp(0.0, 1.0)
+    }
+
+    /// Punto de llegada actual: a `arrival_radii` radios del destino
+    pub fn arrival_point(&self, system: &SolarSystem) -> Option<DVec3> {
+        let body = system.body(self.target)?;
+        Some(body.position() + self.approach * (body.radius as f64 * self.arrival_radii))
     }
 
-    pub fn update(&mut self, dt: f32, camera_pos: &mut Vec3) {
+    pub fn update(&mut self, dt: f32, camera_pos: &mut DVec3, system: &SolarSystem) {
         if !self.active {
             return;
         }
+        let Some(end) = self.arrival_point(system) else {
+            self.active = false;
+            return;
+        };
+        self.end_pos = end;
 
+        let before = smooth_step(self.time / self.duration);
         self.time += dt;
-
         let t = (self.time / self.duration).clamp(0.0, 1.0);
-        let smooth = smooth_step(t);
+        let after = smooth_step(t);
 
-        // interpolate camera
-        *camera_pos = self.start_pos.lerp(&self.end_pos, smooth);
+        // Se replanifica desde la posición actual hacia el destino de este frame y se
+        // avanza la parte del tramo restante que marca la curva de suavizado
+        let fraction = if before >= 1.0 { 1.0 } else { ((after - before) / (1.0 - before)) as f64 };
+        let route = plan_route(*camera_pos, end, system, self.clearance_radii);
+        let next = point_along(&route, fraction);
+        if next.distance(camera_pos) > 1e-9 {
+            self.direction = (next - *camera_pos).normalize();
+        }
+        *camera_pos = next;
 
         if t >= 1.0 {
+            *camera_pos = end;
             self.active = false;
         }
     }
 }
+
+impl Default for WarpEffect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Waypoints de `from` a `to` (ambos incluidos) que rodean los cuerpos del sistema.
+/// Cada segmento que cruza una esfera de seguridad se parte en un waypoint al costado
+pub fn plan_route(from: DVec3, to: DVec3, system: &SolarSystem, clearance_radii: f64) -> Vec<DVec3> {
+    // Si un extremo ya está dentro de la esfera de seguridad, se achica para no encerrarlo
+    let obstacles: Vec<(DVec3, f64)> = system
+        .bodies()
+        .map(|body| {
+            let center = body.position();
+            let r = (body.radius as f64 * clearance_radii)
+                .min(from.distance(&center) * 0.99)
+                .min(to.distance(&center) * 0.99);
+            (center, r)
+        })
+        .collect();
+
+    let mut route = vec![from];
+    detour(from, to, &obstacles, MAX_DETOURS, &mut route);
+    route
+}
+
+fn detour(from: DVec3, to: DVec3, obstacles: &[(DVec3, f64)], depth: usize, route: &mut Vec<DVec3>) {
+    if depth > 0
+        && let Some((center, r, t)) = first_blocking(from, to, obstacles)
+    {
+        let d = to - from;
+        let mut side = from + d * t - center;
+        if side.length_squared() < 1e-12 {
+            side = perpendicular(d);
+        }
+        let waypoint = center + side.normalize() * (r * DETOUR_MARGIN);
+        detour(from, waypoint, obstacles, depth - 1, route);
+        detour(waypoint, to, obstacles, depth - 1, route);
+        return;
+    }
+    route.push(to);
+}
+
+/// Primera esfera que corta el segmento: (centro, radio, parámetro del punto más cercano)
+fn first_blocking(from: DVec3, to: DVec3, obstacles: &[(DVec3, f64)]) -> Option<(DVec3, f64, f64)> {
+    let d = to - from;
+    let len2 = d.length_squared();
+    if len2 < 1e-12 {
+        return None;
+    }
+    obstacles
+        .iter()
+        .filter_map(|&(center, r)| {
+            let t = ((center - from).dot(&d) / len2).clamp(0.0, 1.0);
+            ((from + d * t).distance(&center) < r).then_some((center, r, t))
+        })
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+}
+
+fn perpendicular(d: DVec3) -> DVec3 {
+    let up = DVec3::from_vec3(Vec3::unit_y());
+    let side = d.cross(&up);
+    if side.length_squared() > 1e-12 { side } else { d.cross(&DVec3::from_vec3(Vec3::unit_x())) }
+}
+
+/// Punto a una fracción de la longitud de la ruta, suavizada con Catmull-Rom
+fn point_along(route: &[DVec3], fraction: f64) -> DVec3 {
+    let last = route.len() - 1;
+    let mut points = vec![route[0]];
+    for i in 0..last {
+        let p0 = route[i.saturating_sub(1)];
+        let p3 = route[(i + 2).min(last)];
+        for s in 1..=SAMPLES_PER_SEGMENT {
+            let u = s as f64 / SAMPLES_PER_SEGMENT as f64;
+            points.push(catmull_rom(p0, route[i], route[i + 1], p3, u));
+        }
+    }
+
+    let total: f64 = points.windows(2).map(|w| w[0].distance(&w[1])).sum();
+    let mut remaining = total * fraction.clamp(0.0, 1.0);
+    for w in points.windows(2) {
+        let len = w[0].distance(&w[1]);
+        if remaining <= len && len > 0.0 {
+            return w[0].lerp(&w[1], remaining / len);
+        }
+        remaining -= len;
+    }
+    route[last]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::solar_system::test_system;
+
+
+    /// Corre el warp hasta el final; devuelve la menor distancia a la superficie de cada cuerpo
+    fn fly(warp: &mut WarpEffect, ship: &mut DVec3, system: &mut SolarSystem) -> Vec<f64> {
+        let mut gaps = vec![f64::MAX; system.bodies().count()];
+        while warp.active {
+            system.update(0.01);
+            warp.update(0.01, ship, system);
+            for (gap, body) in gaps.iter_mut().zip(system.bodies()) {
+                *gap = gap.min(ship.distance(&body.position()) - body.radius as f64);
+            }
+        }
+        gaps
+    }
+
+    #[test]
+    fn test_warp_to_unknown_body() {
+        let system = test_system();
+        let mut warp = WarpEffect::new();
+        assert!(warp.warp_to(DVec3::zero(), 9, &system).is_err());
+        assert!(!warp.active);
+    }
+
+    #[test]
+    fn test_clear_route_is_direct() {
+        let system = test_system();
+        let from = DVec3::new(0.0, 50.0, 0.0);
+        let to = DVec3::new(10.0, 50.0, 10.0);
+        assert_eq!(plan_route(from, to, &system, WARP_CLEARANCE_RADII), vec![from, to]);
+    }
+
+    #[test]
+    fn test_routes_around_the_sun() {
+        let mut system = test_system();
+        let target = system.body(3).unwrap().position();
+        // La nave está del otro lado del sol: la línea recta lo atraviesa
+        let mut ship = -target + DVec3::new(0.0, 0.0, 0.5);
+
+        let route = plan_route(ship, target, &system, WARP_CLEARANCE_RADII);
+        assert!(route.len() > 2);
+
+        let mut warp = WarpEffect::new();
+        warp.warp_to(ship, 3, &system).unwrap();
+        let gaps = fly(&mut warp, &mut ship, &mut system);
+        assert!(gaps.iter().all(|&gap| gap > 0.0));
+        // Pasa por fuera de la esfera de seguridad del sol (2 radios)
+        assert!(gaps[0] > system.body(0).unwrap().radius as f64 * 0.9);
+    }
+
+    #[test]
+    fn test_arrives_scaled_by_radius_and_follows_target() {
+        for target in [2, 3] {
+            let mut system = test_system();
+            let mut ship = DVec3::new(0.0, 30.0, -40.0);
+            let mut warp = WarpEffect::new();
+            warp.duration = 5.0; // el planeta avanza bastante durante el viaje
+            warp.warp_to(ship, target, &system).unwrap();
+            let initial_end = warp.end_pos;
+
+            fly(&mut warp, &mut ship, &mut system);
+
+            let body = system.body(target).unwrap();
+            let expected = body.radius as f64 * WARP_ARRIVAL_RADII;
+            assert!((ship.distance(&body.position()) - expected).abs() < 1e-6);
+            assert!(ship.distance(&initial_end) > 1.0);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use crate::effects::skybox::Star;
+use crate::effects::warp::WarpEffect;
+use crate::framebuffer::{Framebuffer, BlendMode, lerp_color, pack, unpack};
+use crate::math::{Mat4, Vec3, smooth_step, world_to_screen};
+
+const STREAK_COLOR: u32 = 0xFFE8F0FF;
+const TUNNEL_COLOR: u32 = 0xFF3050C0;
+const FLASH_COLOR: u32 = 0xFFFFFFFF;
+const STREAK_SEGMENTS: usize = 4; // tramos con brillo decreciente hacia la cola
+const RAMP: f32 = 0.2;            // fracción del warp que tarda en entrar y salir el efecto
+const FLASH_WINDOW: f32 = 0.08;   // el destello crece en el último tramo del warp
+
+/// Intensidad del efecto según el avance del warp: sube al empezar y baja al llegar
+pub fn warp_intensity(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return 0.0;
+    }
+    smooth_step(t / RAMP) * (1.0 - smooth_step((t - (1.0 - RAMP)) / RAMP))
+}
+
+/// Destello de salida: crece al final del warp y llega a 1 al terminar
+pub fn exit_flash(t: f32) -> f32 {
+    smooth_step((t - (1.0 - FLASH_WINDOW)) / FLASH_WINDOW)
+}
+
+/// Efecto de hiperespacio: estelas de estrellas, túnel con blur radial,
+/// golpe de FOV y destello al salir. Todo depende del avance `t` del warp
+pub struct WarpVisuals {
+    pub fov_kick_deg: f32,
+    pub streak_length: f32,  // en unidades de dirección (0 = sin estela)
+    pub blur_strength: f32,  // fracción de la distancia al foco que se difumina
+    pub blur_samples: usize,
+    pub tunnel_tint: f32,
+    pub flash_decay: f32,    // por segundo, una vez terminado el warp
+    progress: Option<f32>,
+    flash: f32,
+}
+
+impl WarpVisuals {
+    pub fn new() -> Self {
+        Self {
+            fov_kick_deg: 20.0,
+            streak_length: 0.5,
+            blur_strength: 0.25,
+            blur_samples: 6,
+            tunnel_tint: 0.5,
+            flash_decay: 3.0,
+            progress: None,
+            flash: 0.0,
+        }
+    }
+
+    /// Sigue al warp; al terminar, el destello se apaga de a poco
+    pub fn update(&mut self, dt: f32, warp: &WarpEffect) {
+        if warp.active {
+            self.set_progress(warp.progress());
+        } else {
+            self.progress = None;
+            self.flash = (self.flash - self.flash_decay * dt).max(0.0);
+        }
+    }
+
+    /// Fija el avance del efecto (para reproducir un frame concreto)
+    pub fn set_progress(&mut self, t: f32) {
+        self.progress = Some(t);
+        self.flash = exit_flash(t);
+    }
+
+    pub fn intensity(&self) -> f32 {
+        self.progress.map_or(0.0, warp_intensity)
+    }
+
+    /// Grados que se suman al FOV de la cámara
+    pub fn fov_kick(&self) -> f32 {
+        self.fov_kick_deg * self.intensity()
+    }
+
+    /// Estira las estrellas en estelas que apuntan hacia la dirección de viaje.
+    /// `view_proj` es la view-projection relativa a la cámara
+    pub fn render_streaks(&self, fb: &mut Framebuffer, stars: &[Star], view_proj: &Mat4, direction: Vec3) {
+        let intensity = self.intensity();
+        if intensity <= 0.0 {
+            return;
+        }
+        let (w, h) = (fb.width as f32, fb.height as f32);
+        let length = self.streak_length * intensity;
+
+        for star in stars {
+            let head = star.direction;
+            let tail = (head + direction * length).normalize();
+            let mut previous = world_to_screen(head * 300.0, view_proj, w, h);
+            for i in 1..=STREAK_SEGMENTS {
+                let u = i as f32 / STREAK_SEGMENTS as f32;
+                let point = world_to_screen(head.lerp(&tail, u).normalize() * 300.0, view_proj, w, h);
+                if let (Some((x0, y0, _)), Some((x1, y1, _))) = (previous, point) {
+                    let alpha = 1.0 - (u - 1.0 / STREAK_SEGMENTS as f32);
+                    fb.blend_line(x0 as i32, y0 as i32, x1 as i32, y1 as i32, STREAK_COLOR, alpha, BlendMode::Additive);
+                }
+                previous = point;
+            }
+        }
+    }
+
+    /// Post-proceso sobre el frame terminado: blur radial hacia el foco de la
+    /// dirección de viaje, tinte de túnel en los bordes y destello
+    pub fn post_process(&self, fb: &mut Framebuffer, view_proj: &Mat4, direction: Vec3) {
+        let intensity = self.intensity();
+        let (w, h) = (fb.width as f32, fb.height as f32);
+        let focus = world_to_screen(direction * 300.0, view_proj, w, h)
+            .map_or((w * 0.5, h * 0.5), |(x, y, _)| (x, y));
+
+        if intensity > 0.0 {
+            let source = fb.buffer.clone();
+            let reach = self.blur_strength * intensity;
+            let samples = self.blur_samples.max(1);
+            let max_r2 = (w * w + h * h) * 0.25;
+
+            for y in 0..fb.height {
+                for x in 0..fb.width {
+                    let (dx, dy) = (focus.0 - x as f32, focus.1 - y as f32);
+                    let mut sum = [0.0f32; 3];
+                    for k in 0..samples {
+                        let s = reach * k as f32 / samples as f32;
+                        let sx = (x as f32 + dx * s).clamp(0.0, w - 1.0) as usize;
+                        let sy = (y as f32 + dy * s).clamp(0.0, h - 1.0) as usize;
+                        let rgb = unpack(source[sy * fb.width + sx]);
+                        for (acc, v) in sum.iter_mut().zip(rgb) {
+                            *acc += v;
+                        }
+                    }
+                    let n = samples as f32;
+                    let blurred = pack(sum.map(|v| v / n));
+
+                    // Tinte del túnel: más fuerte lejos del foco
+                    let r2 = (dx * dx + dy * dy) / max_r2;
+                    fb.buffer[y * fb.width + x] = lerp_color(blurred, TUNNEL_COLOR, self.tunnel_tint * intensity * r2.min(1.0));
+                }
+            }
+        }
+
+        if self.flash > 0.0 {
+            for pixel in fb.buffer.iter_mut() {
+                *pixel = lerp_color(*pixel, FLASH_COLOR, self.flash);
+            }
+        }
+    }
+}
+
+impl Default for WarpVisuals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKGROUND: u32 = 0xFF000A0F;
+
+    /// Frame de 160x90 mirando hacia +Z, solo con las estrellas
+    fn starfield() -> (Framebuffer, Vec<Star>, Mat4) {
+        let view = Mat4::look_at(Vec3::zero(), Vec3::unit_z(), Vec3::unit_y());
+        let view_proj = Mat4::perspective_reversed_z(1.0, 16.0 / 9.0, 0.1, 1000.0) * view;
+        let stars: Vec<Star> = (0..200)
+            .map(|i| {
+                let a = i as f32 * 2.399; // espiral de Fibonacci en el hemisferio de adelante
+                let r = (i as f32 / 200.0).sqrt() * 0.8;
+                let direction = Vec3::new(r * a.cos(), r * a.sin(), 1.0).normalize();
+                Star { direction, magnitude: 1.0, color: 0xFFFFFFFF }
+            })
+            .collect();
+
+        let mut fb = Framebuffer::new(160, 90);
+        fb.clear(BACKGROUND);
+        for star in &stars {
+            if let Some((x, y, _)) = world_to_screen(star.direction * 300.0, &view_proj, 160.0, 90.0) {
+                fb.set_pixel(x as usize, y as usize, 0xFFFFFFFF);
+            }
+        }
+        (fb, stars, view_proj)
+    }
+
+    /// El mismo frame con el warp en `t`
+    fn frame(t: f32) -> Framebuffer {
+        let (mut fb, stars, view_proj) = starfield();
+        let mut visuals = WarpVisuals::new();
+        visuals.set_progress(t);
+        visuals.render_streaks(&mut fb, &stars, &view_proj, Vec3::unit_z());
+        visuals.post_process(&mut fb, &view_proj, Vec3::unit_z());
+        fb
+    }
+
+    fn lit(fb: &Framebuffer) -> usize {
+        fb.buffer.iter().filter(|&&c| c != BACKGROUND).count()
+    }
+
+    fn brightness(fb: &Framebuffer) -> f32 {
+        let sum: u32 = fb.buffer.iter().map(|c| ((c >> 16) & 0xFF) + ((c >> 8) & 0xFF) + (c & 0xFF)).sum();
+        sum as f32 / (fb.buffer.len() * 3) as f32
+    }
+
+    #[test]
+    fn test_intensity_curve() {
+        assert_eq!(warp_intensity(0.0), 0.0);
+        assert_eq!(warp_intensity(1.0), 0.0);
+        assert_eq!(warp_intensity(0.5), 1.0);
+        assert!(warp_intensity(0.05) < warp_intensity(0.15));
+        assert_eq!(exit_flash(0.5), 0.0);
+        assert_eq!(exit_flash(1.0), 1.0);
+
+        let mut visuals = WarpVisuals::new();
+        assert_eq!(visuals.fov_kick(), 0.0);
+        visuals.set_progress(0.5);
+        assert_eq!(visuals.fov_kick(), visuals.fov_kick_deg);
+    }
+
+    #[test]
+    fn test_frame_at_start_is_untouched() {
+        let (stars_only, _, _) = starfield();
+        let before = lit(&stars_only);
+        assert!(before > 0);
+        assert!(frame(0.0).buffer == stars_only.buffer);
+        // Apenas empezado el efecto ya hay estelas, y en pleno warp son más largas
+        assert!(lit(&frame(0.05)) > before);
+        assert!(lit(&frame(0.5)) > lit(&frame(0.05)));
+    }
+
+    #[test]
+    fn test_streaks_never_darken_a_bright_sky() {
+        // Sobre un cielo claro las colas se funden con lo que ya hay, sin dejar rastros oscuros
+        let (mut fb, stars, view_proj) = starfield();
+        fb.buffer.iter_mut().filter(|c| **c == BACKGROUND).for_each(|c| *c = 0xFFC0C8D0);
+        let before = fb.buffer.clone();
+        let mut visuals = WarpVisuals::new();
+        visuals.set_progress(0.5);
+        visuals.render_streaks(&mut fb, &stars, &view_proj, Vec3::unit_z());
+
+        let channels = |c: u32| [(c >> 16) & 0xFF, (c >> 8) & 0xFF, c & 0xFF];
+        assert_ne!(fb.buffer, before);
+        for (after, before) in fb.buffer.iter().zip(&before) {
+            let (a, b) = (channels(*after), channels(*before));
+            assert!((0..3).all(|i| a[i] + 1 >= b[i]), "{after:08X} sobre {before:08X}");
+        }
+    }
+
+    #[test]
+    fn test_tunnel_tints_edges_more_than_center() {
+        let fb = frame(0.5);
+        let blue = |c: u32| (c & 0xFF) as i32 - ((c >> 16) & 0xFF) as i32;
+        let corner = fb.get_pixel(0, 0).unwrap();
+        let center = fb.get_pixel(80, 45).unwrap();
+        assert!(blue(corner) > blue(center));
+    }
+
+    #[test]
+    fn test_exit_flash_brightens_and_fades() {
+        assert!(brightness(&frame(1.0)) > 250.0);
+
+        // Mientras el warp sigue activo el destello sigue a su avance
+        let mut warp = WarpEffect::new();
+        warp.active = true;
+        warp.time = warp.duration * 0.97;
+        let mut visuals = WarpVisuals::new();
+        visuals.update(0.1, &warp);
+        let held = exit_flash(warp.progress());
+        assert!(held > 0.0);
+        assert_eq!(visuals.flash, held);
+
+        // Al terminar se apaga de a poco
+        warp.active = false;
+        visuals.update(0.1, &warp);
+        assert!(visuals.flash > 0.0 && visuals.flash < held);
+        visuals.update(1.0, &warp);
+        assert_eq!(visuals.flash, 0.0);
+    }
+}
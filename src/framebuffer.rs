@@ -1,3 +1,80 @@
+/// Convención del depth buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthMode {
+    /// z de NDC en [-1, 1], menor = más cerca. Pierde precisión lejos
+    Standard,
+    /// z de NDC en [0, 1] invertido (cerca = 1, lejos = 0), mayor = más cerca.
+    /// Combinado con floats reparte la precisión casi uniformemente en distancia
+    ReversedZ,
+}
+
+impl DepthMode {
+    /// Valor con el que se limpia el z-buffer (lo más lejano posible)
+    pub fn clear_value(&self) -> f32 {
+        match self {
+            DepthMode::Standard => f32::INFINITY,
+            DepthMode::ReversedZ => f32::NEG_INFINITY,
+        }
+    }
+
+    /// z de NDC del plano lejano: lo que está en el infinito (el cielo) se
+    /// dibuja con esta profundidad y solo pasa donde no hay geometría
+    pub fn far_value(&self) -> f32 {
+        match self {
+            DepthMode::Standard => 1.0,
+            DepthMode::ReversedZ => 0.0,
+        }
+    }
+
+    /// Verdadero si `z` está más cerca que el valor ya almacenado
+    #[inline]
+    pub fn passes(&self, z: f32, stored: f32) -> bool {
+        match self {
+            DepthMode::Standard => z < stored,
+            DepthMode::ReversedZ => z > stored,
+        }
+    }
+}
+
+/// Cómo se combina un color nuevo con lo que ya hay en el framebuffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// Reemplaza el color y escribe profundidad
+    #[default]
+    Opaque,
+    /// Mezcla por opacidad (superficies translúcidas: anillos, nubes)
+    Alpha,
+    /// Suma la luz (brillos, atmósferas, escape de motores)
+    Additive,
+    /// Oscurece multiplicando (sombras, filtros)
+    #[allow(dead_code)]
+    Multiply,
+}
+
+impl BlendMode {
+    /// Combina `src` con opacidad `alpha` sobre `dst`
+    pub fn blend(&self, dst: u32, src: u32, alpha: f32) -> u32 {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let channel = |shift: u32| {
+            let d = ((dst >> shift) & 0xFF) as f32;
+            let s = ((src >> shift) & 0xFF) as f32;
+            let value = match self {
+                BlendMode::Opaque => s,
+                BlendMode::Alpha => d + (s - d) * alpha,
+                BlendMode::Additive => d + s * alpha,
+                BlendMode::Multiply => d * (1.0 + (s / 255.0 - 1.0) * alpha),
+            };
+            (value.min(255.0) as u32) << shift
+        };
+        0xFF000000 | channel(16) | channel(8) | channel(0)
+    }
+
+    /// Solo lo opaco escribe en el z-buffer; lo translúcido solo lo consulta
+    pub fn writes_depth(&self) -> bool {
+        *self == BlendMode::Opaque
+    }
+}
+
 /// Framebuffer para renderizado por software
 /// Contiene el buffer de color y el z-buffer (depth buffer)
 pub struct Framebuffer {
@@ -5,6 +82,9 @@ pub struct Framebuffer {
     pub height: usize,
     pub buffer: Vec<u32>,   // Color buffer en formato 0xAARRGGBB
     pub zbuffer: Vec<f32>,  // Depth buffer para oclusión correcta
+    pub emission: Vec<f32>, // Brillo extra (HDR) de superficies emisivas, para el bloom
+    pub depth_mode: DepthMode,
+    emission_level: f32,
 }
 
 impl Framebuffer {
@@ -16,13 +96,37 @@ impl Framebuffer {
             height,
             buffer: vec![0; size],
             zbuffer: vec![f32::INFINITY; size],
+            emission: vec![0.0; size],
+            depth_mode: DepthMode::Standard,
+            emission_level: 0.0,
         }
     }
 
+    /// Cambia la convención del z-buffer (debe coincidir con la proyección)
+    pub fn set_depth_mode(&mut self, mode: DepthMode) {
+        self.depth_mode = mode;
+        self.zbuffer.fill(mode.clear_value());
+    }
+
     /// Limpia el framebuffer con un color sólido
+    #[allow(dead_code)]
     pub fn clear(&mut self, color: u32) {
         self.buffer.fill(color);
-        self.zbuffer.fill(f32::INFINITY);
+        self.zbuffer.fill(self.depth_mode.clear_value());
+        self.emission.fill(0.0);
+    }
+
+    /// Limpia profundidad y emisión sin tocar el color, para cuando el fondo
+    /// cubre toda la pantalla
+    pub fn clear_depth(&mut self) {
+        self.zbuffer.fill(self.depth_mode.clear_value());
+        self.emission.fill(0.0);
+    }
+
+    /// Emisión que se registra con cada píxel opaco que se dibuje desde ahora.
+    /// Lo que tape a una superficie emisiva con 0 también tapa su brillo
+    pub fn set_emission_level(&mut self, level: f32) {
+        self.emission_level = level;
     }
 
     /// Establece un píxel en la posición (x, y) con el color especificado
@@ -35,17 +139,45 @@ impl Framebuffer {
     }
 
     /// Establece un píxel con depth testing
-    /// Solo dibuja si la profundidad z es menor que la almacenada
+    /// Solo dibuja si la profundidad z está más cerca que la almacenada
     pub fn set_pixel_with_depth(&mut self, x: usize, y: usize, color: u32, z: f32) {
         if x < self.width && y < self.height {
             let index = y * self.width + x;
-            if z < self.zbuffer[index] {
+            if self.depth_mode.passes(z, self.zbuffer[index]) {
                 self.buffer[index] = color;
                 self.zbuffer[index] = z;
+                self.emission[index] = self.emission_level;
             }
         }
     }
 
+    /// Combina `color` con opacidad `alpha` sobre lo ya dibujado, sin depth test
+    /// (overlays de pantalla)
+    pub fn blend_pixel(&mut self, x: usize, y: usize, color: u32, alpha: f32, mode: BlendMode) {
+        if x < self.width && y < self.height {
+            let index = y * self.width + x;
+            self.buffer[index] = mode.blend(self.buffer[index], color, alpha);
+        }
+    }
+
+    /// Combina `color` sobre lo ya dibujado con depth test. Salvo en modo opaco
+    /// no escribe profundidad (superficies translúcidas)
+    pub fn blend_pixel_with_depth(&mut self, x: usize, y: usize, color: u32, alpha: f32, z: f32, mode: BlendMode) {
+        if mode.writes_depth() {
+            self.set_pixel_with_depth(x, y, color, z);
+        } else if self.depth_test(x, y, z) {
+            let index = y * self.width + x;
+            self.buffer[index] = mode.blend(self.buffer[index], color, alpha);
+        }
+    }
+
+    /// Verdadero si `z` pasa el depth test en (x, y), sin escribir nada
+    pub fn depth_test(&self, x: usize, y: usize, z: f32) -> bool {
+        x < self.width
+            && y < self.height
+            && self.depth_mode.passes(z, self.zbuffer[y * self.width + x])
+    }
+
     /// Obtiene el color de un píxel (útil para debugging)
     pub fn get_pixel(&self, x: usize, y: usize) -> Option<u32> {
         if x < self.width && y < self.height {
@@ -55,36 +187,30 @@ impl Framebuffer {
         }
     }
 
-    /// Dibuja una línea usando el algoritmo de Bresenham
-    /// Útil para renderizar órbitas y wireframes
-    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u32) {
-        let mut x0 = x0;
-        let mut y0 = y0;
-        let dx = (x1 - x0).abs();
-        let dy = -(y1 - y0).abs();
-        let sx = if x0 < x1 { 1 } else { -1 };
-        let sy = if y0 < y1 { 1 } else { -1 };
-        let mut err = dx + dy;
-
-        loop {
-            if x0 >= 0 && x0 < self.width as i32 && y0 >= 0 && y0 < self.height as i32 {
-                self.set_pixel(x0 as usize, y0 as usize, color);
-            }
-
-            if x0 == x1 && y0 == y1 {
-                break;
+    /// Línea de Bresenham que se combina con lo ya dibujado en vez de pisarlo
+    #[allow(clippy::too_many_arguments)]
+    pub fn blend_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u32, alpha: f32, mode: BlendMode) {
+        line_pixels(x0, y0, x1, y1, |x, y, _| {
+            if x >= 0 && y >= 0 {
+                self.blend_pixel(x as usize, y as usize, color, alpha, mode);
             }
+        });
+    }
 
-            let e2 = 2 * err;
-            if e2 >= dy {
-                err += dy;
-                x0 += sx;
-            }
-            if e2 <= dx {
-                err += dx;
-                y0 += sy;
+    /// Dibuja una línea de Bresenham con depth test (sin escribir profundidad),
+    /// para que órbitas y trayectorias queden ocultas detrás de los planetas
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_line(
+        &mut self,
+        x0: i32, y0: i32, z0: f32,
+        x1: i32, y1: i32, z1: f32,
+        color: u32,
+    ) {
+        line_pixels(x0, y0, x1, y1, |x, y, t| {
+            if x >= 0 && y >= 0 && self.depth_test(x as usize, y as usize, z0 + (z1 - z0) * t) {
+                self.set_pixel(x as usize, y as usize, color);
             }
-        }
+        });
     }
 
     /// Dibuja un círculo (útil para órbitas)
@@ -122,6 +248,38 @@ impl Framebuffer {
     }
 }
 
+/// Recorre con Bresenham los píxeles de (x0, y0) a (x1, y1), ambos incluidos.
+/// `visit` recibe cada píxel y en qué fracción [0, 1] de la línea está
+fn line_pixels(x0: i32, y0: i32, x1: i32, y1: i32, mut visit: impl FnMut(i32, i32, f32)) {
+    let (start_x, start_y) = (x0, y0);
+    let mut x0 = x0;
+    let mut y0 = y0;
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let steps = dx.max(-dy).max(1) as f32;
+
+    loop {
+        visit(x0, y0, (x0 - start_x).abs().max((y0 - start_y).abs()) as f32 / steps);
+
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+}
+
 /// Helper para crear colores en formato 0xAARRGGBB
 #[allow(dead_code)]
 pub fn color_from_rgb(r: u8, g: u8, b: u8) -> u32 {
@@ -129,7 +287,6 @@ pub fn color_from_rgb(r: u8, g: u8, b: u8) -> u32 {
 }
 
 /// Helper para interpolar colores
-#[allow(dead_code)]
 pub fn lerp_color(color1: u32, color2: u32, t: f32) -> u32 {
     let t = t.clamp(0.0, 1.0);
     
@@ -146,4 +303,78 @@ pub fn lerp_color(color1: u32, color2: u32, t: f32) -> u32 {
     let b = (b1 + (b2 - b1) * t) as u32;
     
     0xFF000000 | (r << 16) | (g << 8) | b
-}
\ No newline at end of file
+}
+
+/// Canales RGB de un color en [0, 1]
+pub fn unpack(color: u32) -> [f32; 3] {
+    [
+        ((color >> 16) & 0xFF) as f32 / 255.0,
+        ((color >> 8) & 0xFF) as f32 / 255.0,
+        (color & 0xFF) as f32 / 255.0,
+    ]
+}
+
+/// Color opaco a partir de canales RGB en [0, 1] (los que se pasan se recortan)
+pub fn pack(color: [f32; 3]) -> u32 {
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u32;
+    0xFF000000 | (channel(color[0]) << 16) | (channel(color[1]) << 8) | channel(color[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend_respects_depth_without_writing_it() {
+        let mut fb = Framebuffer::new(2, 1);
+        fb.set_depth_mode(DepthMode::ReversedZ);
+        fb.clear(0xFF000000);
+        fb.set_pixel_with_depth(1, 0, 0xFF0000FF, 0.5);
+
+        fb.blend_pixel_with_depth(0, 0, 0xFFFFFFFF, 0.5, 0.3, BlendMode::Alpha);
+        assert_eq!(fb.get_pixel(0, 0), Some(0xFF7F7F7F));
+        assert_eq!(fb.zbuffer[0], f32::NEG_INFINITY);
+
+        // Detrás de lo ya dibujado no se mezcla
+        fb.blend_pixel_with_depth(1, 0, 0xFFFFFFFF, 0.5, 0.3, BlendMode::Additive);
+        assert_eq!(fb.get_pixel(1, 0), Some(0xFF0000FF));
+    }
+
+    #[test]
+    fn test_emission_follows_depth_writes() {
+        let mut fb = Framebuffer::new(2, 1);
+        fb.set_depth_mode(DepthMode::ReversedZ);
+        fb.clear(0xFF000000);
+
+        fb.set_emission_level(2.0);
+        fb.set_pixel_with_depth(0, 0, 0xFFFFDD44, 0.2);
+        fb.set_pixel_with_depth(1, 0, 0xFFFFDD44, 0.2);
+        fb.set_emission_level(0.0);
+        // Algo más cerca tapa el brillo; algo más lejos no
+        fb.set_pixel_with_depth(0, 0, 0xFF44AAFF, 0.5);
+        fb.set_pixel_with_depth(1, 0, 0xFF44AAFF, 0.1);
+        assert_eq!(fb.emission, vec![0.0, 2.0]);
+
+        fb.clear(0xFF000000);
+        assert_eq!(fb.emission, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_blend_modes() {
+        let dst = 0xFF804020;
+        assert_eq!(BlendMode::Opaque.blend(dst, 0xFF102030, 0.5), 0xFF102030);
+        assert_eq!(BlendMode::Alpha.blend(dst, 0xFF000000, 0.5), 0xFF402010);
+        assert_eq!(BlendMode::Alpha.blend(dst, 0xFFFFFFFF, 0.0), dst);
+        // La suma satura en 255
+        assert_eq!(BlendMode::Additive.blend(dst, 0xFFFF0010, 1.0), 0xFFFF4030);
+        assert_eq!(BlendMode::Multiply.blend(dst, 0xFF808080, 1.0), 0xFF402010);
+        // Multiplicar con alfa 0 no cambia nada
+        assert_eq!(BlendMode::Multiply.blend(dst, 0xFF000000, 0.0), dst);
+
+        let mut fb = Framebuffer::new(1, 1);
+        fb.clear(0xFF000000);
+        fb.blend_pixel(0, 0, 0xFF204060, 0.5, BlendMode::Additive);
+        fb.blend_pixel(0, 0, 0xFF204060, 0.5, BlendMode::Additive);
+        assert_eq!(fb.get_pixel(0, 0), Some(0xFF204060));
+    }
+}
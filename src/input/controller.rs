@@ -1,63 +1,230 @@
-use minifb::{Window, Key, MouseMode};
-use crate::camera::freecam::FreeCamera;
+use minifb::{Window, Key, KeyRepeat, MouseButton, MouseMode};
+use crate::math::Vec3;
+use crate::models::SpaceShip;
+use crate::physics::ManeuverPlanner;
+use crate::camera::CameraInput;
+use crate::scene::solar_system::SolarSystem;
+use super::target_selector::TargetSelector;
 
 pub struct InputController {
     last_mouse_pos: Option<(f32, f32)>,
+    left_was_down: bool,
+    pub mouse_sensitivity: f32,
 }
 
 impl InputController {
     pub fn new() -> Self {
         Self {
             last_mouse_pos: None,
+            left_was_down: false,
+            mouse_sensitivity: 0.05,
         }
     }
 
-    pub fn update(&mut self, window: &Window, camera: &mut FreeCamera, dt: f32) {
-        // ============= MOVIMIENTO =============
-        let mut speed_mult = 1.0;
+    /// Traduce teclado y mouse a empuje y torque de la nave
+    pub fn update(&mut self, window: &Window, ship: &mut SpaceShip) {
+        // ============= EMPUJE =============
+        // Ejes locales de la nave: +Z adelante, +X izquierda en pantalla, +Y arriba
+        let mut thrust = Vec3::zero();
+
+        if window.is_key_down(Key::W) {
+            thrust.z += 1.0;
+        }
+        if window.is_key_down(Key::S) {
+            thrust.z -= 1.0;
+        }
+        if window.is_key_down(Key::A) {
+            thrust.x += 1.0;
+        }
+        if window.is_key_down(Key::D) {
+            thrust.x -= 1.0;
+        }
+        if window.is_key_down(Key::Space) {
+            thrust.y += 1.0;
+        }
+        if window.is_key_down(Key::LeftCtrl) {
+            thrust.y -= 1.0;
+        }
 
         if window.is_key_down(Key::LeftShift) {
-            speed_mult = 3.0;
+            thrust = thrust * ship.boost;
         }
 
+        ship.set_thrust(thrust);
+
+        if window.is_key_pressed(Key::F, KeyRepeat::No) {
+            ship.flight_assist = !ship.flight_assist;
+        }
+
+        // ============= TORQUE =============
+        let mut torque = Vec3::zero();
+
+        // Mouse arriba = nariz arriba (giro negativo en X local),
+        // mouse a la derecha = girar a la derecha (giro negativo en Y local)
+        let (dx, dy) = self.mouse_delta(window);
+        torque.x = dy * self.mouse_sensitivity;
+        torque.y = -dx * self.mouse_sensitivity;
+
+        // Roll
+        if window.is_key_down(Key::Q) {
+            torque.z -= 1.0;
+        }
+        if window.is_key_down(Key::E) {
+            torque.z += 1.0;
+        }
+
+        ship.set_torque(torque);
+    }
+
+    /// Entrada para las cámaras que no pilotan la nave
+    pub fn camera_input(&mut self, window: &Window) -> CameraInput {
+        let mut movement = Vec3::zero();
         if window.is_key_down(Key::W) {
-            camera.move_forward(dt * speed_mult);
+            movement.z += 1.0;
         }
         if window.is_key_down(Key::S) {
-            camera.move_backward(dt * speed_mult);
+            movement.z -= 1.0;
         }
         if window.is_key_down(Key::A) {
-            camera.move_left(dt * speed_mult);
+            movement.x += 1.0;
         }
         if window.is_key_down(Key::D) {
-            camera.move_right(dt * speed_mult);
+            movement.x -= 1.0;
         }
         if window.is_key_down(Key::Space) {
-            camera.position.y += camera.speed * dt * speed_mult;
+            movement.y += 1.0;
         }
         if window.is_key_down(Key::LeftCtrl) {
-            camera.position.y -= camera.speed * dt * speed_mult;
+            movement.y -= 1.0;
+        }
+
+        let mut roll = 0.0;
+        if window.is_key_down(Key::Q) {
+            roll -= 1.0;
+        }
+        if window.is_key_down(Key::E) {
+            roll += 1.0;
+        }
+
+        CameraInput {
+            mouse_delta: self.mouse_delta(window),
+            dragging: window.get_mouse_down(MouseButton::Right),
+            scroll: window.get_scroll_wheel().map_or(0.0, |(_, y)| y.signum()),
+            movement,
+            roll,
+            boost: window.is_key_down(Key::LeftShift),
+            toggle_mode: window.is_key_pressed(Key::M, KeyRepeat::No),
         }
+    }
+
+    /// Desplazamiento del mouse desde la última llamada
+    fn mouse_delta(&mut self, window: &Window) -> (f32, f32) {
+        let Some((mx, my)) = window.get_mouse_pos(MouseMode::Pass) else {
+            return (0.0, 0.0);
+        };
+        let delta = self.last_mouse_pos.map_or((0.0, 0.0), |(lx, ly)| (mx - lx, my - ly));
+        self.last_mouse_pos = Some((mx, my));
+        delta
+    }
 
-        // ============= MOUSE LOOK =============
-        if let Some((mx, my)) = window.get_mouse_pos(MouseMode::Pass) {
-            if let Some((lx, ly)) = self.last_mouse_pos {
-                let dx = mx - lx;
-                let dy = my - ly;
+    /// Posición del mouse en el frame en que se aprieta el botón izquierdo
+    pub fn click(&mut self, window: &Window) -> Option<(f32, f32)> {
+        let down = window.get_mouse_down(MouseButton::Left);
+        let pressed = down && !self.left_was_down;
+        self.left_was_down = down;
+
+        if pressed {
+            window.get_mouse_pos(MouseMode::Discard)
+        } else {
+            None
+        }
+    }
 
-                camera.yaw += dx * camera.mouse_sensitivity;
-                camera.pitch -= dy * camera.mouse_sensitivity;
+    /// Lista de destinos: Tab la abre y recorre (Shift+Tab hacia atrás), las letras
+    /// buscan por nombre, Backspace borra (o cierra si no hay texto) y Enter confirma.
+    /// Devuelve el cuerpo elegido al confirmar
+    pub fn update_targets(&mut self, window: &Window, selector: &mut TargetSelector, system: &mut SolarSystem) -> Option<usize> {
+        if !selector.open {
+            if window.is_key_pressed(Key::Tab, KeyRepeat::No) {
+                selector.open(system);
+            }
+            return None;
+        }
 
-                // Limitar pitch
-                let max_pitch = 1.5_f32;
-                if camera.pitch > max_pitch {
-                    camera.pitch = max_pitch;
+        for key in window.get_keys_pressed(KeyRepeat::Yes) {
+            match key {
+                Key::Tab => {
+                    let back = window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift);
+                    selector.cycle(system, if back { -1 } else { 1 });
                 }
-                if camera.pitch < -max_pitch {
-                    camera.pitch = -max_pitch;
+                Key::Enter | Key::NumPadEnter => return selector.confirm(system),
+                Key::Backspace if selector.query.is_empty() => selector.close(),
+                Key::Backspace => selector.backspace(system),
+                key => {
+                    if let Some(c) = key_char(key) {
+                        selector.type_char(c, system);
+                    }
                 }
             }
-            self.last_mouse_pos = Some((mx, my));
         }
+        None
+    }
+
+    /// Edición del nodo de maniobra: crear/borrar, componentes de Δv y tiempo
+    pub fn update_maneuver(&mut self, window: &Window, planner: &mut ManeuverPlanner, dt: f32) {
+        const DV_RATE: f64 = 2.0;   // unidades de Δv por segundo con la tecla apretada
+        const TIME_RATE: f64 = 5.0; // segundos de nodo por segundo
+
+        if window.is_key_pressed(Key::N, KeyRepeat::No) {
+            planner.toggle_node(10.0);
+        }
+        if window.is_key_pressed(Key::X, KeyRepeat::No) {
+            planner.auto_execute = !planner.auto_execute;
+        }
+
+        let Some(node) = planner.node.as_mut() else {
+            return;
+        };
+        let dt = dt as f64;
+
+        if window.is_key_down(Key::Up) {
+            node.prograde += DV_RATE * dt;
+        }
+        if window.is_key_down(Key::Down) {
+            node.prograde -= DV_RATE * dt;
+        }
+        if window.is_key_down(Key::Right) {
+            node.radial += DV_RATE * dt;
+        }
+        if window.is_key_down(Key::Left) {
+            node.radial -= DV_RATE * dt;
+        }
+        if window.is_key_down(Key::PageUp) {
+            node.normal += DV_RATE * dt;
+        }
+        if window.is_key_down(Key::PageDown) {
+            node.normal -= DV_RATE * dt;
+        }
+        if window.is_key_down(Key::Period) {
+            node.time += TIME_RATE * dt;
+        }
+        if window.is_key_down(Key::Comma) {
+            node.time = (node.time - TIME_RATE * dt).max(0.0);
+        }
+    }
+}
+
+/// Carácter que escribe una tecla (letras y números)
+fn key_char(key: Key) -> Option<char> {
+    const LETTERS: [Key; 26] = [
+        Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M,
+        Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    ];
+    const DIGITS: [Key; 10] = [
+        Key::Key0, Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5, Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+    ];
+    if let Some(i) = LETTERS.iter().position(|&k| k == key) {
+        return Some((b'a' + i as u8) as char);
     }
+    DIGITS.iter().position(|&k| k == key).map(|i| (b'0' + i as u8) as char)
 }
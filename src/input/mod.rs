@@ -1,3 +1,5 @@
 pub mod controller;
+pub mod target_selector;
 
 pub use controller::*;
+pub use target_selector::*;
@@ -0,0 +1,181 @@
+use crate::scene::solar_system::SolarSystem;
+
+/// Lista de destinos para el warp. El cuerpo elegido es la selección del sistema
+/// (`SolarSystem::selected`), así se resalta igual que con el mouse
+pub struct TargetSelector {
+    pub open: bool,
+    pub query: String, // texto escrito para buscar por nombre (en minúsculas)
+}
+
+impl TargetSelector {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+        }
+    }
+
+    /// Abre la lista; sin selección previa arranca en el primer cuerpo
+    pub fn open(&mut self, system: &mut SolarSystem) {
+        self.open = true;
+        self.query.clear();
+        if system.selected.is_none() {
+            system.selected = Some(0);
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.query.clear();
+    }
+
+    /// Cuerpos que coinciden con lo escrito: primero por prefijo y, si no hay
+    /// ninguno, por contenido. Sin texto son todos
+    pub fn matches(&self, system: &SolarSystem) -> Vec<usize> {
+        if self.query.is_empty() {
+            return (0..system.body_count()).collect();
+        }
+        let names: Vec<String> = system.bodies().map(|b| b.name.to_lowercase()).collect();
+        let find = |prefix: bool| -> Vec<usize> {
+            names
+                .iter()
+                .enumerate()
+                .filter(|(_, name)| if prefix { name.starts_with(&self.query) } else { name.contains(&self.query) })
+                .map(|(i, _)| i)
+                .collect()
+        };
+        let by_prefix = find(true);
+        if by_prefix.is_empty() { find(false) } else { by_prefix }
+    }
+
+    /// Pasa al siguiente (o anterior, con `step` negativo) de los que coinciden
+    pub fn cycle(&mut self, system: &mut SolarSystem, step: isize) {
+        let matches = self.matches(system);
+        if matches.is_empty() {
+            return;
+        }
+        let n = matches.len() as isize;
+        let next = match system.selected.and_then(|s| matches.iter().position(|&m| m == s)) {
+            Some(i) => (i as isize + step).rem_euclid(n),
+            None => if step < 0 { n - 1 } else { 0 },
+        };
+        system.selected = Some(matches[next as usize]);
+    }
+
+    /// Agrega una letra a la búsqueda y salta al primer cuerpo que coincide
+    pub fn type_char(&mut self, c: char, system: &mut SolarSystem) {
+        self.query.extend(c.to_lowercase());
+        self.select_first(system);
+    }
+
+    pub fn backspace(&mut self, system: &mut SolarSystem) {
+        self.query.pop();
+        self.select_first(system);
+    }
+
+    /// Cierra la lista y devuelve el cuerpo elegido
+    pub fn confirm(&mut self, system: &SolarSystem) -> Option<usize> {
+        self.close();
+        system.selected
+    }
+
+    /// Texto para el título de la ventana: búsqueda y cuerpos que coinciden
+    pub fn readout(&self, system: &SolarSystem) -> String {
+        let list: Vec<String> = self
+            .matches(system)
+            .into_iter()
+            .filter_map(|i| {
+                let name = &system.body(i)?.name;
+                Some(if system.selected == Some(i) { format!("[{}]", name) } else { name.clone() })
+            })
+            .collect();
+        format!("Destino: {}_ | {} | Enter: warp", self.query, list.join(" "))
+    }
+
+    /// Elige el cuerpo cuyo nombre es exactamente lo escrito o, si no hay, el
+    /// primero que coincide
+    fn select_first(&self, system: &mut SolarSystem) {
+        let exact = system.find_body(&self.query);
+        if let Some(body) = exact.or_else(|| self.matches(system).first().copied()) {
+            system.selected = Some(body);
+        }
+    }
+}
+
+impl Default for TargetSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::solar_system::test_system;
+
+
+    #[test]
+    fn test_cycle_wraps_and_includes_the_sun() {
+        let mut system = test_system();
+        let mut selector = TargetSelector::new();
+        selector.open(&mut system);
+        assert_eq!(system.selected, Some(0));
+
+        for expected in [1, 2, 3, 0] {
+            selector.cycle(&mut system, 1);
+            assert_eq!(system.selected, Some(expected));
+        }
+        selector.cycle(&mut system, -1);
+        assert_eq!(system.selected, Some(3));
+    }
+
+    #[test]
+    fn test_type_ahead_by_name() {
+        let mut system = test_system();
+        let mut selector = TargetSelector::new();
+        selector.open(&mut system);
+
+        selector.type_char('P', &mut system);
+        assert_eq!(system.selected, Some(1));
+        assert_eq!(selector.matches(&system), vec![1, 2, 3]);
+
+        // Tab recorre solo los que coinciden
+        selector.cycle(&mut system, -1);
+        assert_eq!(system.selected, Some(3));
+
+        // Sin prefijo que coincida se busca dentro del nombre
+        selector.query.clear();
+        selector.type_char('b', &mut system);
+        assert_eq!(system.selected, Some(2));
+
+        selector.backspace(&mut system);
+        assert_eq!(selector.query, "");
+        assert!(selector.readout(&system).contains("Sun"));
+    }
+
+    #[test]
+    fn test_confirm_returns_choice_and_closes() {
+        let mut system = test_system();
+        let mut selector = TargetSelector::new();
+        selector.open(&mut system);
+        for c in "planetc".chars() {
+            selector.type_char(c, &mut system);
+        }
+        assert_eq!(selector.confirm(&system), Some(3));
+        assert!(!selector.open);
+        assert!(selector.query.is_empty());
+    }
+
+    #[test]
+    fn test_exact_name_wins_over_prefix() {
+        let mut system = test_system();
+        system.planets[0].name = "PlanetC2".to_string();
+        let mut selector = TargetSelector::new();
+        selector.open(&mut system);
+        for c in "planetc".chars() {
+            selector.type_char(c, &mut system);
+        }
+        assert_eq!(selector.matches(&system), vec![1, 3]);
+        assert_eq!(system.selected, Some(3));
+    }
+}
@@ -11,26 +11,65 @@ mod effects;
 mod physics;
 mod input;
 
-use framebuffer::Framebuffer;
-use math::{Vec3, Mat4, deg_to_rad, create_transform_matrix};
-use camera::freecam::FreeCamera;
+use framebuffer::{Framebuffer, DepthMode};
+use math::{Vec3, DVec3, Mat4, deg_to_rad, world_to_screen};
+use camera::{
+    Camera, CameraContext, CameraInput, CameraPath, ChaseCamera, CockpitCamera, FixedCamera, FreeCamera,
+    OrbitCamera, PathCamera, PathRecorder,
+};
 use scene::solar_system::SolarSystem;
-use models::{generate_sphere, load_obj, Mesh};
+use models::{generate_sphere, load_obj, SpaceShip};
 use renderer::pipeline::Pipeline;
+use renderer::transparent::TransparentPass;
+use renderer::lines::{draw_polyline_3d, LineFade};
 use effects::skybox::Skybox;
+use effects::background::{NebulaSettings, SkyBackground};
 use effects::warp::WarpEffect;
-use physics::resolve_camera_collisions;
-use input::InputController;
+use effects::warp_visuals::WarpVisuals;
+use effects::bloom::Bloom;
+use effects::sun::{Corona, LensFlare};
+use physics::{
+    resolve_ship_collisions, ContactTracker, CollisionResponse, CollisionSettings, gravity_at, dominant_body, OrbitalElements, TrajectoryPredictor,
+    TrajectoryPoint, relative_to_body, PickRay, ManeuverPlanner, state_at, hohmann_between,
+};
+use input::{InputController, TargetSelector};
 
 const WIDTH: usize = 1280;
 const HEIGHT: usize = 720;
 
+// ========== PROYECCIÓN ==========
+const FOV_DEG: f32 = 60.0;
+const BACKGROUND_COLOR: u32 = 0xFF000A0F;
+const SKY_SEED: u64 = 1977;
+const SKY_FILLER_STARS: usize = 1200;
+const STAR_CATALOG_FILE: &str = "assets/stars/bright_stars.csv";
+const CONSTELLATIONS_FILE: &str = "assets/stars/constellations.txt";
+const SKY_BACKGROUND_FILE: &str = "assets/sky/background.ppm"; // opcional, equirectangular
+const NEAR: f32 = 0.1;
+const FAR: f32 = 100_000.0;
+const DEPTH_MODE: DepthMode = DepthMode::ReversedZ;
+
 // ========== CONFIG DE LA NAVE ==========
 const SHIP_SCALE: f32 = 0.20;     
-const SHIP_DISTANCE: f32 = 6.0;  
-const SHIP_HEIGHT_OFFSET: f32 = -1.0; 
-const SHIP_YAW_OFFSET: f32 = 0.0; 
-const SHIP_PITCH_OFFSET: f32 = 0.0;   
+
+// ========== CÁMARAS ==========
+const CHASE_DISTANCE: f32 = 6.0;      // la cámara de persecución va detrás de la nave
+const CHASE_HEIGHT: f32 = 1.0;        // y un poco por encima
+const FIXED_CAMERA_RANGE: f32 = 40.0; // la cámara fija se recoloca más allá de esto
+const CAMERA_PATH_FILE: &str = "assets/paths/camera_path.txt";
+const PATH_RECORD_INTERVAL: f32 = 1.0; // segundos entre keyframes grabados
+
+// ========== COLISIONES DE LA NAVE ==========
+const SHIP_COLLISIONS: CollisionSettings = CollisionSettings {
+    response: CollisionResponse::Bounce { restitution: 0.3 },
+    crash_speed: Some(12.0), // más rápido que esto, la nave se estrella
+};
+
+// ========== PREDICCIÓN DE TRAYECTORIA ==========
+const PREDICTION_HORIZON: f64 = 30.0; // segundos
+const PREDICTION_STEP: f64 = 0.05;
+const TRAJECTORY_COLOR: u32 = 0xFF44FFFF;
+const MANEUVER_COLOR: u32 = 0xFFFFAA33;
 
 fn main() {
     // -------------------------
@@ -46,26 +85,64 @@ fn main() {
 
     let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
     let mut pipeline = Pipeline::new(WIDTH, HEIGHT);
+    framebuffer.set_depth_mode(DEPTH_MODE);
+    pipeline.set_depth_mode(DEPTH_MODE);
 
     // -------------------------
     // Cámara
     // -------------------------
-    let mut camera = FreeCamera::new(Vec3::new(0.0, 5.0, 30.0));
 
     let mut input = InputController::new();
 
     // -------------------------
     // Objetos
     // -------------------------
-    let skybox = Skybox::new(300);
+    let mut skybox = load_sky();
+    let sky_clock = Instant::now(); // reloj del titileo de las estrellas
+    let background = load_background();
+
+    // sol: corona, bloom y destellos de lente
+    let corona = Corona::default();
+    let lens_flare = LensFlare::default();
+    let mut bloom = Bloom::new();
     let sphere_mesh = generate_sphere(16, 16);
     let mut solar_system = SolarSystem::new(sphere_mesh);
 
     // Nave OBJ
     let ship_mesh = load_obj("assets/models/ship.obj", SHIP_SCALE);
+    let mut ship = SpaceShip::new(ship_mesh);
+    ship.set_scale(SHIP_SCALE);
+    ship.position = DVec3::new(0.0, 5.0, 36.0);
+    let ship_start = ship.position;
+
+    // Modos de cámara, se alternan con V. Las de la nave leen su transform
+    let mut cameras: Vec<Box<dyn Camera>> = vec![
+        Box::new(ChaseCamera::new(CHASE_DISTANCE, CHASE_HEIGHT)),
+        Box::new(CockpitCamera::new()),
+        Box::new(FixedCamera::new(FIXED_CAMERA_RANGE)),
+        Box::new(FreeCamera::new(ship_start + DVec3::new(0.0, 0.0, -CHASE_DISTANCE as f64))),
+        Box::new(OrbitCamera::new(0)),
+    ];
+    let mut active_camera = 0;
+
+    // Recorridos de cámara: grabación desde la cámara activa y reproducción
+    let mut recorder: Option<PathRecorder> = None;
+    let mut player: Option<PathCamera> = None;
 
     // Warp
     let mut warp = WarpEffect::new();
+    let mut warp_visuals = WarpVisuals::new();
+    let mut target_selector = TargetSelector::new();
+
+    // Trayectoria predicha y órbitas
+    let mut predictor = TrajectoryPredictor::new(PREDICTION_HORIZON, PREDICTION_STEP);
+    let mut show_orbits = true;
+
+    // Nodo de maniobra
+    let mut planner = ManeuverPlanner::new();
+
+    // Contacto actual de la nave (para avisar solo al tocar un cuerpo)
+    let mut contact = ContactTracker::default();
 
     // -------------------------
     // FPS
@@ -79,12 +156,33 @@ fn main() {
     println!("  Sistema Solar - Software Renderer (Rust)");
     println!("===========================================");
     println!("Controles:");
-    println!("  WASD - mover");
-    println!("  SHIFT - rápido");
-    println!("  Mouse - rotar cámara");
-    println!("  SPACE - subir");
-    println!("  CTRL - bajar");
+    println!("  WASD - empuje adelante/atrás/lateral");
+    println!("  SHIFT - boost");
+    println!("  Mouse - pitch/yaw de la nave");
+    println!("  Q/E - roll (nave y cámara libre en modo 6DOF)");
+    println!("  SPACE - empuje arriba");
+    println!("  CTRL - empuje abajo");
+    println!("  F - asistencia de vuelo on/off");
+    println!("  O - mostrar/ocultar órbitas y trayectoria");
+    println!("  L - mostrar/ocultar constelaciones");
+    println!("  [ / ] - horizonte de predicción");
+    println!("  N - crear/borrar nodo de maniobra");
+    println!("  Flechas - Δv prograde/radial del nodo");
+    println!("  RePág/AvPág - Δv normal del nodo");
+    println!("  , / . - mover el nodo en el tiempo");
+    println!("  X - auto-ejecutar la maniobra");
+    println!("  R - reiniciar la nave tras estrellarse");
+    println!("  H - transferencias de Hohmann entre planetas");
+    println!("  V - cambiar de cámara (persecución / cabina / fija / libre / órbita)");
+    println!("  K - grabar / guardar recorrido de cámara");
+    println!("  J - keyframe manual (mira al cuerpo seleccionado)");
+    println!("  P - reproducir / detener el recorrido");
+    println!("  Clic - seleccionar cuerpo");
+    println!("  Arrastrar (botón derecho) / rueda - girar y zoom de las cámaras libre y órbita");
+    println!("  M - cámara libre: modo FPS / 6DOF");
+    println!("  G - warp al cuerpo seleccionado");
     println!("  1/2/3 - warp a planetas");
+    println!("  Tab - lista de destinos (Tab/Shift+Tab recorre, escribir busca por nombre, Enter hace warp, Backspace borra/cierra)");
     println!("  ESC - salir");
     println!("===========================================\n");
 
@@ -100,59 +198,212 @@ fn main() {
         // =======================
         // INPUT
         // =======================
-        input.update(&window, &mut camera, dt);
+        // Lista de destinos: mientras está abierta, el teclado escribe el nombre
+        let typing = target_selector.open;
+        let mut warp_target = input.update_targets(&window, &mut target_selector, &mut solar_system);
 
-        // Warp shortcuts
-        if window.is_key_pressed(Key::Key1, KeyRepeat::No) {
-            if let Some(p) = solar_system.planets.get(0) {
-                warp.start(camera.position, p.position() + Vec3::new(0.0, 3.0, 12.0));
+        if !typing {
+            if window.is_key_pressed(Key::V, KeyRepeat::No) {
+                active_camera = (active_camera + 1) % cameras.len();
             }
-        }
-        if window.is_key_pressed(Key::Key2, KeyRepeat::No) {
-            if let Some(p) = solar_system.planets.get(1) {
-                warp.start(camera.position, p.position() + Vec3::new(0.0, 3.0, 12.0));
+
+            if window.is_key_pressed(Key::K, KeyRepeat::No) {
+                match recorder.take() {
+                    Some(rec) => match rec.path.save(CAMERA_PATH_FILE) {
+                        Ok(()) => println!("Recorrido guardado en {} ({} keyframes)", CAMERA_PATH_FILE, rec.path.keyframes.len()),
+                        Err(e) => println!("No se pudo guardar el recorrido: {}", e),
+                    },
+                    None => recorder = Some(PathRecorder::new(PATH_RECORD_INTERVAL)),
+                }
+            }
+            if window.is_key_pressed(Key::P, KeyRepeat::No) {
+                player = match player {
+                    Some(_) => None,
+                    None => match CameraPath::load(CAMERA_PATH_FILE) {
+                        Ok(path) => Some(PathCamera::new(path)),
+                        Err(e) => {
+                            println!("No se pudo cargar el recorrido: {}", e);
+                            None
+                        }
+                    },
+                };
             }
         }
-        if window.is_key_pressed(Key::Key3, KeyRepeat::No) {
-            if let Some(p) = solar_system.planets.get(2) {
-                warp.start(camera.position, p.position() + Vec3::new(0.0, 3.0, 12.0));
+
+        // Teclado y mouse pilotan la nave o mueven la cámara, según el modo
+        let camera_input = if typing {
+            ship.set_thrust(Vec3::zero());
+            ship.set_torque(Vec3::zero());
+            CameraInput::default()
+        } else if player.is_none() && cameras[active_camera].controls_ship() {
+            input.update(&window, &mut ship);
+            CameraInput::default()
+        } else {
+            ship.set_thrust(Vec3::zero());
+            ship.set_torque(Vec3::zero());
+            input.camera_input(&window)
+        };
+
+        if !typing {
+            input.update_maneuver(&window, &mut planner, dt);
+
+            if window.is_key_pressed(Key::H, KeyRepeat::No) {
+                print_hohmann_table(&solar_system);
+            }
+
+            // Warp shortcuts
+            for (key, target) in [(Key::Key1, 1), (Key::Key2, 2), (Key::Key3, 3)] {
+                if window.is_key_pressed(key, KeyRepeat::No) {
+                    warp_target = Some(target);
+                }
             }
+
+            // Warp al cuerpo seleccionado (mouse o lista de destinos)
+            if window.is_key_pressed(Key::G, KeyRepeat::No) && solar_system.selected.is_some() {
+                warp_target = solar_system.selected;
+            }
+
+            if window.is_key_pressed(Key::R, KeyRepeat::No) && ship.crashed.is_some() {
+                ship.crashed = None;
+                ship.halt();
+                ship.position = ship_start;
+            }
+
+            if window.is_key_pressed(Key::O, KeyRepeat::No) {
+                show_orbits = !show_orbits;
+            }
+            if window.is_key_pressed(Key::L, KeyRepeat::No) {
+                skybox.show_constellations = !skybox.show_constellations;
+            }
+            if window.is_key_pressed(Key::LeftBracket, KeyRepeat::No) {
+                predictor.horizon = (predictor.horizon * 0.5).max(5.0);
+            }
+            if window.is_key_pressed(Key::RightBracket, KeyRepeat::No) {
+                predictor.horizon = (predictor.horizon * 2.0).min(600.0);
+            }
+        }
+
+        if let Some(target) = warp_target
+            && let Err(e) = warp.warp_to(ship.position, target, &solar_system)
+        {
+            println!("{}", e);
         }
 
-        warp.update(dt, &mut camera.position);
+        let previous_position = ship.position;
+        if warp.active {
+            ship.halt();
+            warp.update(dt, &mut ship.position, &solar_system);
+        } else {
+            let reference = solar_system.body(dominant_body(&solar_system, ship.position));
+            if let Some(body) = reference {
+                let rel_pos = ship.position - body.position();
+                let rel_vel = ship.velocity - body.velocity();
+                planner.update(dt, &mut ship, rel_pos, rel_vel);
+            }
+            ship.update(dt, gravity_at(&solar_system, ship.position));
+        }
         solar_system.update(dt);
 
-        // colisión cámara vs planetas
-        resolve_camera_collisions(&solar_system, &mut camera.position, 1.5);
+        // colisión continua nave vs cuerpos; se avisa solo al entrar en contacto
+        let hit = resolve_ship_collisions(
+            &mut ship,
+            previous_position,
+            &solar_system,
+            dt,
+            SHIP_COLLISIONS,
+        );
+        let new_contact = contact.update(hit.as_ref(), &ship, &solar_system);
+        if let Some(hit) = hit.filter(|hit| new_contact || hit.crashed) {
+            if hit.crashed {
+                println!("La nave se estrelló contra {} a {:.1} u/s (R para reiniciar)", hit.body_name, hit.impact_speed);
+            } else {
+                println!("Impacto con {} a {:.1} u/s", hit.body_name, hit.impact_speed);
+            }
+        }
+
+        // cámara activa (o el recorrido que se está reproduciendo)
+        let playing = player.is_some();
+        let camera: &mut dyn Camera = match player.as_mut() {
+            Some(p) => p,
+            None => cameras[active_camera].as_mut(),
+        };
+        camera.update(&camera_input, &CameraContext { system: &solar_system, ship: &ship, dt });
+        let camera_pos = camera.position();
+        let fov = camera.fov_deg().unwrap_or(FOV_DEG);
+
+        // durante una reproducción no se graba (sería copiar el recorrido)
+        if let Some(rec) = recorder.as_mut().filter(|_| !playing) {
+            // J: keyframe manual, mirando al cuerpo seleccionado si lo hay
+            if window.is_key_pressed(Key::J, KeyRepeat::No) {
+                rec.add_key(camera, fov, solar_system.selected);
+            }
+            rec.update(dt, camera, fov);
+        }
 
         // =======================
         // Matrices
         // =======================
+        // El golpe de FOV del warp solo afecta a la proyección, no a lo que se graba
+        warp_visuals.update(dt, &warp);
         let view = camera.view_matrix();
-        let aspect = WIDTH as f32 / HEIGHT as f32;
-        let projection = Mat4::perspective(deg_to_rad(60.0), aspect, 0.1, 1000.0);
+        let projection = pipeline.projection_matrix(deg_to_rad(fov + warp_visuals.fov_kick()), NEAR, FAR);
+        let warp_direction = warp.direction.to_vec3();
+
+        // Selección con clic: el rayo sale de la cámara por el píxel del mouse
+        if let Some((mx, my)) = input.click(&window) {
+            select_body(&mut solar_system, &ship, mx, my, projection * view, camera_pos);
+        }
 
         // =======================
         // RENDER
         // =======================
-        framebuffer.clear(0xFF000A0F);
+        framebuffer.clear_depth();
+
+        // fondo: Vía Láctea y nebulosas
+        background.render(&mut framebuffer, &(projection * view));
 
-        // skybox
-        skybox.render(&mut framebuffer, view, projection);
+        // skybox (estirado en estelas durante el warp)
+        skybox.render(&mut framebuffer, view, projection, sky_clock.elapsed().as_secs_f32());
+        warp_visuals.render_streaks(&mut framebuffer, skybox.stars(), &(projection * view), warp_direction);
 
 
         // sistema solar
-        solar_system.render(&mut framebuffer, &mut pipeline, view, projection);
-
-        // nave
-        render_ship(
-            &mut framebuffer,
-            &mut pipeline,
-            &camera,
-            &ship_mesh,
-            view,
-            projection,
-        );
+        solar_system.render(&mut framebuffer, &mut pipeline, view, projection, camera_pos);
+
+        // órbitas y trayectoria predicha (después de los cuerpos para el depth test)
+        if show_orbits {
+            let view_proj = projection * view;
+            solar_system.render_orbits(&mut framebuffer, view_proj, camera_pos, NEAR);
+            render_trajectory(&mut framebuffer, &solar_system, &ship, &predictor, &planner, view_proj, camera_pos);
+        }
+
+        // nave (no desde la cabina)
+        if camera.shows_ship() {
+            render_ship(&mut framebuffer, &mut pipeline, &ship, camera_pos, view, projection);
+        }
+
+        // superficies translúcidas (corona, atmósferas, anillos), de atrás hacia adelante sobre lo opaco
+        let view_proj = projection * view;
+        let sun_center = (solar_system.sun.position() - camera_pos).to_vec3();
+        let sun_radius = solar_system.sun.radius;
+        let sky_time = sky_clock.elapsed().as_secs_f32();
+        let mut transparent = TransparentPass::new();
+        transparent.push(sun_center.length(), |fb, _| corona.render(fb, &view_proj, sun_center, sun_radius, sky_time));
+        solar_system.queue_transparent(&mut transparent, view_proj, camera_pos);
+        transparent.flush(&mut framebuffer, &mut pipeline);
+
+        // lo emisivo (el sol) y lo muy brillante se derrama en bloom; el destello
+        // de lente solo aparece si el z-buffer deja ver el sol
+        bloom.apply(&mut framebuffer);
+        lens_flare.render(&mut framebuffer, &view_proj, sun_center, sun_radius);
+
+        // túnel y destello del warp sobre el frame terminado
+        warp_visuals.post_process(&mut framebuffer, &(projection * view), warp_direction);
+
+        let camera_name = camera.name();
+        if player.as_ref().is_some_and(|p| p.finished()) {
+            player = None;
+        }
 
         // =======================
         // Mostrar
@@ -165,9 +416,20 @@ fn main() {
         // FPS
         // =======================
         fps_count += 1;
-        if fps_timer.elapsed().as_secs() >= 1 {
+        if target_selector.open {
+            // Mientras se elige destino, el título muestra la lista
+            window.set_title(&format!("Sistema Solar | {}", target_selector.readout(&solar_system)));
+        } else if fps_timer.elapsed().as_secs() >= 1 {
             let fps = fps_count as f32 / fps_timer.elapsed().as_secs_f32();
-            window.set_title(&format!("Sistema Solar | FPS: {:.1}", fps));
+            window.set_title(&format!(
+                "Sistema Solar | FPS: {:.1} | Cámara: {}{} | {}{}{}",
+                fps,
+                camera_name,
+                if recorder.is_some() { " [REC]" } else { "" },
+                orbit_readout(&solar_system, &ship),
+                maneuver_readout(&planner, &ship),
+                selection_readout(&solar_system, &ship)
+            ));
             fps_count = 0;
             fps_timer = Instant::now();
         }
@@ -177,47 +439,17 @@ fn main() {
 
 
 // =========================================
-// Render de nave (HUD 3D correcto)
+// Render de nave
 // =========================================
 fn render_ship(
     framebuffer: &mut Framebuffer,
     pipeline: &mut Pipeline,
-    camera: &FreeCamera,
-    ship_mesh: &Mesh,
+    ship: &SpaceShip,
+    camera_pos: DVec3,
     view: Mat4,
     projection: Mat4,
 ) {
-    // Direcciones de la cámara
-    let forward = camera.forward();
-    let up = camera.up();
-
-    // --------------------------
-    // POSICIÓN FINAL DE LA NAVE
-    // --------------------------
-    let ship_pos =
-        camera.position
-        + forward * SHIP_DISTANCE
-        + Vec3::new(0.0, SHIP_HEIGHT_OFFSET, 0.0);
-
-    // --------------------------
-    // ORIENTACIÓN REAL DE LA NAVE
-    // Ajuste universal: rotar -90° en Y
-    // para que el frente mire adelante
-    // --------------------------
-    let yaw = camera.yaw + SHIP_YAW_OFFSET - std::f32::consts::FRAC_PI_2;
-    let pitch = camera.pitch + SHIP_PITCH_OFFSET;
-
-    let ship_rot = Vec3::new(pitch, yaw, 0.0);
-
-    // --------------------------
-    // ESCALA FINAL
-    // --------------------------
-    let ship_scale = Vec3::new(SHIP_SCALE, SHIP_SCALE, SHIP_SCALE);
-
-    // --------------------------
-    // MATRIZ FINAL
-    // --------------------------
-    let model = create_transform_matrix(ship_pos, ship_rot, ship_scale);
+    let model = ship.model_matrix(camera_pos);
     let mvp = projection * view * model;
 
     pipeline.set_mvp(mvp);
@@ -225,7 +457,173 @@ fn render_ship(
 
     pipeline.draw_mesh(
         framebuffer,
-        &ship_mesh.vertices,
-        &ship_mesh.faces,
+        &ship.mesh.vertices,
+        &ship.mesh.faces,
+    );
+}
+
+// =========================================
+// Trayectoria futura de la nave
+// =========================================
+fn render_trajectory(
+    framebuffer: &mut Framebuffer,
+    system: &SolarSystem,
+    ship: &SpaceShip,
+    predictor: &TrajectoryPredictor,
+    planner: &ManeuverPlanner,
+    view_proj: Mat4,
+    camera_pos: DVec3,
+) {
+    let prediction = predictor.predict(system, ship.position, ship.velocity);
+
+    // En el marco del cuerpo dominante la órbita se ve cerrada
+    let Some(body) = system.body(dominant_body(system, ship.position)) else {
+        return;
+    };
+    let to_camera = |points: Vec<DVec3>| -> Vec<Vec3> {
+        points.into_iter().map(|p| (p - camera_pos).to_vec3()).collect()
+    };
+
+    let fade = LineFade { start: 40.0, end: 300.0 };
+    let points = to_camera(relative_to_body(&prediction, body));
+    draw_polyline_3d(framebuffer, &points, &view_proj, NEAR, TRAJECTORY_COLOR, fade);
+
+    // Nodo de maniobra: marcador y órbita resultante
+    let Some(node) = planner.node else {
+        return;
+    };
+    let Some(at_node) = state_at(&prediction, node.time) else {
+        return;
+    };
+
+    let rel_pos = at_node.position - body.position_at(node.time);
+    let rel_vel = at_node.velocity - body.velocity_at(node.time);
+    let after_burn = TrajectoryPoint {
+        velocity: at_node.velocity + node.delta_v_vector(rel_pos, rel_vel),
+        ..at_node
+    };
+    let preview = TrajectoryPredictor::new(node.time + predictor.horizon, predictor.step)
+        .predict_from(system, after_burn);
+
+    let points = to_camera(relative_to_body(&preview, body));
+    draw_polyline_3d(framebuffer, &points, &view_proj, NEAR, MANEUVER_COLOR, fade);
+
+    let marker = (rel_pos + body.position() - camera_pos).to_vec3();
+    let (w, h) = (framebuffer.width as f32, framebuffer.height as f32);
+    if let Some((x, y, _)) = world_to_screen(marker, &view_proj, w, h) {
+        framebuffer.draw_circle(x as i32, y as i32, 6, MANEUVER_COLOR);
+    }
+}
+
+// =========================================
+// Selección de cuerpos con el mouse
+// =========================================
+fn select_body(system: &mut SolarSystem, ship: &SpaceShip, x: f32, y: f32, view_proj: Mat4, camera_pos: DVec3) {
+    let Some(ray) = PickRay::from_screen(x, y, WIDTH as f32, HEIGHT as f32, &view_proj, camera_pos) else {
+        return;
+    };
+    let hit = system.pick(&ray);
+
+    // La nave tapa lo que hay detrás: un clic sobre ella no cambia la selección
+    let limit = hit.map_or(f32::INFINITY, |h| h.distance as f32);
+    if ship.ray_cast(ray.origin, ray.direction.to_vec3(), limit).is_some() {
+        return;
+    }
+
+    system.selected = hit.map(|h| h.body);
+}
+
+// =========================================
+// Transferencias de Hohmann entre planetas consecutivos
+// =========================================
+fn print_hohmann_table(system: &SolarSystem) {
+    println!("Transferencias de Hohmann:");
+    for from in 1..=system.planets.len() {
+        for to in [from.saturating_sub(1), from + 1] {
+            let (Some(a), Some(b)) = (system.body(from), system.body(to)) else {
+                continue;
+            };
+            if let Some((t, wait)) = hohmann_between(system, from, to) {
+                println!("  {} -> {}: {}, ventana en {:.1}s", a.name, b.name, t, wait);
+            }
+        }
+    }
+}
+
+// =========================================
+// Órbita de la nave respecto al cuerpo dominante
+// =========================================
+fn orbit_readout(system: &SolarSystem, ship: &SpaceShip) -> String {
+    let index = dominant_body(system, ship.position);
+    let Some(body) = system.body(index) else {
+        return String::new();
+    };
+
+    let elements = OrbitalElements::from_state(
+        ship.position - body.position(),
+        ship.velocity - body.velocity(),
+        body.mu,
     );
+    format!("Órbita {}: {}", body.name, elements)
+}
+
+// =========================================
+// Cielo: catálogo de estrellas con relleno aleatorio
+// =========================================
+fn load_sky() -> Skybox {
+    let mut skybox = match Skybox::load_catalog(STAR_CATALOG_FILE) {
+        Ok(skybox) => skybox,
+        Err(e) => {
+            println!("No se pudo cargar el catálogo de estrellas: {}", e);
+            return Skybox::new(SKY_FILLER_STARS, SKY_SEED);
+        }
+    };
+    if let Err(e) = skybox.load_constellations(CONSTELLATIONS_FILE) {
+        println!("No se pudieron cargar las constelaciones: {}", e);
+    }
+    skybox.add_random_stars(SKY_FILLER_STARS, SKY_SEED);
+    skybox
+}
+
+// =========================================
+// Fondo: imagen de assets si existe, si no procedural
+// =========================================
+fn load_background() -> SkyBackground {
+    if std::path::Path::new(SKY_BACKGROUND_FILE).exists() {
+        match SkyBackground::load_ppm(SKY_BACKGROUND_FILE) {
+            Ok(background) => return background,
+            Err(e) => println!("No se pudo cargar {}: {}", SKY_BACKGROUND_FILE, e),
+        }
+    }
+    SkyBackground::generate(&NebulaSettings { seed: SKY_SEED, base: BACKGROUND_COLOR, ..Default::default() })
+}
+
+// =========================================
+// Cuerpo seleccionado
+// =========================================
+fn selection_readout(system: &SolarSystem, ship: &SpaceShip) -> String {
+    let Some(body) = system.selected_body() else {
+        return String::new();
+    };
+    let distance = (body.position() - ship.position).length() - body.radius as f64;
+    format!(" | Sel: {} a {:.1}", body.name, distance)
+}
+
+// =========================================
+// Estado del nodo de maniobra
+// =========================================
+fn maneuver_readout(planner: &ManeuverPlanner, ship: &SpaceShip) -> String {
+    let Some(node) = planner.node else {
+        return String::new();
+    };
+    format!(
+        " | Nodo T-{:.1}s Δv {:.2} (pro {:.2} nor {:.2} rad {:.2}) encendido {:.1}s{}",
+        node.time,
+        node.delta_v(),
+        node.prograde,
+        node.normal,
+        node.radial,
+        node.burn_time(ship.max_thrust, ship.mass),
+        if planner.auto_execute { " [auto]" } else { "" }
+    )
 }
@@ -1,3 +1,6 @@
+// API matemática general: no todo lo que expone lo usa el binario
+#![allow(dead_code)]
+
 use super::vector::{Vec3, Vec4};
 use std::ops::Mul;
 
@@ -171,6 +174,22 @@ impl Mat4 {
         }
     }
 
+    /// Crea una proyección en perspectiva con reversed-Z:
+    /// el plano cercano va a z = 1 y el lejano a z = 0 en NDC.
+    /// Usar con `DepthMode::ReversedZ` (test de profundidad "mayor gana")
+    pub fn perspective_reversed_z(fov: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let tan_half_fov = (fov / 2.0).tan();
+
+        Self {
+            m: [
+                [1.0 / (aspect * tan_half_fov), 0.0, 0.0, 0.0],
+                [0.0, 1.0 / tan_half_fov, 0.0, 0.0],
+                [0.0, 0.0, near / (far - near), (far * near) / (far - near)],
+                [0.0, 0.0, -1.0, 0.0],
+            ],
+        }
+    }
+
     /// Crea una matriz de proyección ortográfica
     pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
         Self {
@@ -194,6 +213,135 @@ impl Mat4 {
         result
     }
 
+    /// Determinante de la matriz (expansión por cofactores con menores 2x2)
+    pub fn determinant(&self) -> f32 {
+        let (s, c) = self.minors_2x2();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    /// Inversa general. Devuelve `None` si la matriz es singular
+    pub fn inverse(&self) -> Option<Self> {
+        let m = &self.m;
+        let (s, c) = self.minors_2x2();
+
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+        if det.abs() < f32::EPSILON * f32::EPSILON {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let r = [
+            [
+                (m[1][1] * c[5] - m[1][2] * c[4] + m[1][3] * c[3]) * inv_det,
+                (-m[0][1] * c[5] + m[0][2] * c[4] - m[0][3] * c[3]) * inv_det,
+                (m[3][1] * s[5] - m[3][2] * s[4] + m[3][3] * s[3]) * inv_det,
+                (-m[2][1] * s[5] + m[2][2] * s[4] - m[2][3] * s[3]) * inv_det,
+            ],
+            [
+                (-m[1][0] * c[5] + m[1][2] * c[2] - m[1][3] * c[1]) * inv_det,
+                (m[0][0] * c[5] - m[0][2] * c[2] + m[0][3] * c[1]) * inv_det,
+                (-m[3][0] * s[5] + m[3][2] * s[2] - m[3][3] * s[1]) * inv_det,
+                (m[2][0] * s[5] - m[2][2] * s[2] + m[2][3] * s[1]) * inv_det,
+            ],
+            [
+                (m[1][0] * c[4] - m[1][1] * c[2] + m[1][3] * c[0]) * inv_det,
+                (-m[0][0] * c[4] + m[0][1] * c[2] - m[0][3] * c[0]) * inv_det,
+                (m[3][0] * s[4] - m[3][1] * s[2] + m[3][3] * s[0]) * inv_det,
+                (-m[2][0] * s[4] + m[2][1] * s[2] - m[2][3] * s[0]) * inv_det,
+            ],
+            [
+                (-m[1][0] * c[3] + m[1][1] * c[1] - m[1][2] * c[0]) * inv_det,
+                (m[0][0] * c[3] - m[0][1] * c[1] + m[0][2] * c[0]) * inv_det,
+                (-m[3][0] * s[3] + m[3][1] * s[1] - m[3][2] * s[0]) * inv_det,
+                (m[2][0] * s[3] - m[2][1] * s[1] + m[2][2] * s[0]) * inv_det,
+            ],
+        ];
+
+        Some(Self { m: r })
+    }
+
+    /// Inversa rápida para matrices afines (última fila = 0 0 0 1), como las TRS.
+    /// Invierte solo la parte 3x3 y la traslación. Devuelve `None` si es singular
+    pub fn inverse_affine(&self) -> Option<Self> {
+        let inv3 = self.inverse_3x3()?;
+        let t = [self.m[0][3], self.m[1][3], self.m[2][3]];
+
+        let mut result = Self::identity();
+        for (row, inv_row) in result.m.iter_mut().zip(inv3.iter()) {
+            row[..3].copy_from_slice(inv_row);
+            row[3] = -(inv_row[0] * t[0] + inv_row[1] * t[1] + inv_row[2] * t[2]);
+        }
+        Some(result)
+    }
+
+    /// Matriz para transformar normales: inversa transpuesta de la parte 3x3.
+    /// Corrige las normales bajo escala no uniforme. Sin traslación
+    pub fn normal_matrix(&self) -> Option<Self> {
+        let inv3 = self.inverse_3x3()?;
+
+        let mut result = Self::identity();
+        for (i, row) in result.m.iter_mut().take(3).enumerate() {
+            for (j, value) in row.iter_mut().take(3).enumerate() {
+                *value = inv3[j][i];
+            }
+        }
+        Some(result)
+    }
+
+    // Inversa de la submatriz 3x3 superior izquierda
+    fn inverse_3x3(&self) -> Option<[[f32; 3]; 3]> {
+        let m = &self.m;
+        let c00 = m[1][1] * m[2][2] - m[1][2] * m[2][1];
+        let c01 = m[1][2] * m[2][0] - m[1][0] * m[2][2];
+        let c02 = m[1][0] * m[2][1] - m[1][1] * m[2][0];
+
+        let det = m[0][0] * c00 + m[0][1] * c01 + m[0][2] * c02;
+        if det.abs() < f32::EPSILON * f32::EPSILON {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        Some([
+            [
+                c00 * inv_det,
+                (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv_det,
+                (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv_det,
+            ],
+            [
+                c01 * inv_det,
+                (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv_det,
+                (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv_det,
+            ],
+            [
+                c02 * inv_det,
+                (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv_det,
+                (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv_det,
+            ],
+        ])
+    }
+
+    // Menores 2x2 de las dos filas superiores (s) y de las dos inferiores (c)
+    fn minors_2x2(&self) -> ([f32; 6], [f32; 6]) {
+        let m = &self.m;
+        let s = [
+            m[0][0] * m[1][1] - m[1][0] * m[0][1],
+            m[0][0] * m[1][2] - m[1][0] * m[0][2],
+            m[0][0] * m[1][3] - m[1][0] * m[0][3],
+            m[0][1] * m[1][2] - m[1][1] * m[0][2],
+            m[0][1] * m[1][3] - m[1][1] * m[0][3],
+            m[0][2] * m[1][3] - m[1][2] * m[0][3],
+        ];
+        let c = [
+            m[2][0] * m[3][1] - m[3][0] * m[2][1],
+            m[2][0] * m[3][2] - m[3][0] * m[2][2],
+            m[2][0] * m[3][3] - m[3][0] * m[2][3],
+            m[2][1] * m[3][2] - m[3][1] * m[2][2],
+            m[2][1] * m[3][3] - m[3][1] * m[2][3],
+            m[2][2] * m[3][3] - m[3][2] * m[2][3],
+        ];
+        (s, c)
+    }
+
     /// Multiplica la matriz por un Vec4
     pub fn mul_vec4(&self, v: Vec4) -> Vec4 {
         Vec4::new(
@@ -242,6 +390,9 @@ impl Mul for Mat4 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::create_transform_matrix;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
     #[test]
     fn test_identity() {
@@ -259,6 +410,90 @@ mod tests {
         assert_eq!(result, Vec3::new(6.0, 12.0, 18.0));
     }
 
+    fn assert_near_identity(m: &Mat4) {
+        let id = Mat4::identity();
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((m.m[i][j] - id.m[i][j]).abs() < 1e-4, "{:?}", m);
+            }
+        }
+    }
+
+    fn random_trs(rng: &mut StdRng) -> Mat4 {
+        let t = Vec3::new(rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0));
+        let r = Vec3::new(rng.gen_range(-3.0..3.0), rng.gen_range(-3.0..3.0), rng.gen_range(-3.0..3.0));
+        let s = Vec3::new(rng.gen_range(0.2..4.0), rng.gen_range(0.2..4.0), rng.gen_range(0.2..4.0));
+        create_transform_matrix(t, r, s)
+    }
+
+    #[test]
+    fn test_determinant() {
+        assert!((Mat4::identity().determinant() - 1.0).abs() < 1e-6);
+        assert!((Mat4::scale(2.0, 3.0, 4.0).determinant() - 24.0).abs() < 1e-5);
+        assert!((Mat4::rotation_axis(Vec3::new(1.0, 2.0, 3.0), 0.8).determinant() - 1.0).abs() < 1e-5);
+        assert_eq!(Mat4::zero().determinant(), 0.0);
+    }
+
+    #[test]
+    fn test_inverse_random_trs() {
+        let mut rng = StdRng::seed_from_u64(27);
+        for _ in 0..100 {
+            let m = random_trs(&mut rng);
+            let inv = m.inverse().expect("TRS con escala no nula es invertible");
+            assert_near_identity(&(m * inv));
+            assert_near_identity(&(inv * m));
+        }
+    }
+
+    #[test]
+    fn test_inverse_affine_matches_general() {
+        let mut rng = StdRng::seed_from_u64(270);
+        for _ in 0..100 {
+            let m = random_trs(&mut rng);
+            let fast = m.inverse_affine().unwrap();
+            assert_near_identity(&(m * fast));
+
+            let general = m.inverse().unwrap();
+            for i in 0..4 {
+                for j in 0..4 {
+                    assert!((fast.m[i][j] - general.m[i][j]).abs() < 1e-3);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_inverse_perspective() {
+        let p = Mat4::perspective(1.0, 16.0 / 9.0, 0.1, 1000.0);
+        assert_near_identity(&(p * p.inverse().unwrap()));
+    }
+
+    #[test]
+    fn test_inverse_singular() {
+        assert!(Mat4::zero().inverse().is_none());
+        assert!(Mat4::scale(1.0, 0.0, 1.0).inverse().is_none());
+        assert!(Mat4::scale(1.0, 0.0, 1.0).inverse_affine().is_none());
+        assert!(Mat4::scale(0.0, 1.0, 1.0).normal_matrix().is_none());
+    }
+
+    #[test]
+    fn test_normal_matrix_non_uniform_scale() {
+        // Plano inclinado 45°: normal (1, 1, 0) / √2, tangente (1, -1, 0) / √2
+        let model = Mat4::scale(4.0, 1.0, 1.0);
+        let tangent = model.mul_direction(Vec3::new(1.0, -1.0, 0.0));
+        let normal = model
+            .normal_matrix()
+            .unwrap()
+            .mul_direction(Vec3::new(1.0, 1.0, 0.0));
+
+        // La normal transformada sigue perpendicular a la superficie
+        assert!(tangent.dot(&normal).abs() < 1e-5);
+
+        // Y la matriz del modelo la habría inclinado mal
+        let naive = model.mul_direction(Vec3::new(1.0, 1.0, 0.0));
+        assert!(tangent.dot(&naive).abs() > 1.0);
+    }
+
     #[test]
     fn test_scale() {
         let scale = Mat4::scale(2.0, 3.0, 4.0);
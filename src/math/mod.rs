@@ -3,8 +3,10 @@
 pub mod vector;
 pub mod matrix;
 pub mod transforms;
+pub mod quaternion;
 
 // Re-exportar los tipos más usados
-pub use vector::{Vec3, Vec4};
+pub use vector::{Vec3, Vec4, DVec3};
 pub use matrix::Mat4;
+pub use quaternion::Quat;
 pub use transforms::*;
\ No newline at end of file
@@ -0,0 +1,637 @@
+// API matemática general: no todo lo que expone lo usa el binario
+#![allow(dead_code)]
+
+use super::matrix::Mat4;
+use super::vector::Vec3;
+use std::ops::{Mul, Neg};
+
+/// Cuaternión unitario (x, y, z, w) para representar rotaciones 3D
+/// sin gimbal lock. Convención de Hamilton: v' = q * v * q⁻¹
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quat {
+    /// Crea un cuaternión con los componentes dados (no lo normaliza)
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Rotación nula
+    pub fn identity() -> Self {
+        Self::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    /// Crea una rotación de `angle` radianes alrededor de `axis`
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let axis = axis.normalize();
+        let half = angle * 0.5;
+        let s = half.sin();
+        Self::new(axis.x * s, axis.y * s, axis.z * s, half.cos())
+    }
+
+    /// Devuelve (eje, ángulo) de la rotación. Para la identidad el eje es X
+    pub fn to_axis_angle(self) -> (Vec3, f32) {
+        let q = if self.w < 0.0 { -self } else { self }.normalize();
+        let angle = 2.0 * q.w.clamp(-1.0, 1.0).acos();
+        let s = (1.0 - q.w * q.w).max(0.0).sqrt();
+
+        if s < 1e-6 {
+            (Vec3::unit_x(), 0.0)
+        } else {
+            (Vec3::new(q.x / s, q.y / s, q.z / s), angle)
+        }
+    }
+
+    /// Crea una rotación desde ángulos de Euler (pitch, yaw, roll) en radianes.
+    /// Usa el mismo orden que `create_transform_matrix`: Z * Y * X
+    pub fn from_euler(pitch: f32, yaw: f32, roll: f32) -> Self {
+        let qx = Self::from_axis_angle(Vec3::unit_x(), pitch);
+        let qy = Self::from_axis_angle(Vec3::unit_y(), yaw);
+        let qz = Self::from_axis_angle(Vec3::unit_z(), roll);
+        qz * qy * qx
+    }
+
+    /// Convierte a ángulos de Euler (pitch, yaw, roll), inverso de `from_euler`
+    pub fn to_euler(self) -> Vec3 {
+        let m = self.to_mat4().m;
+
+        let sin_yaw = -m[2][0];
+        if sin_yaw.abs() > 0.9999 {
+            // Gimbal lock: el roll se absorbe en el pitch
+            let yaw = std::f32::consts::FRAC_PI_2.copysign(sin_yaw);
+            let pitch = (m[0][1] * sin_yaw).atan2(m[1][1]);
+            return Vec3::new(pitch, yaw, 0.0);
+        }
+
+        let pitch = m[2][1].atan2(m[2][2]);
+        let yaw = sin_yaw.asin();
+        let roll = m[1][0].atan2(m[0][0]);
+        Vec3::new(pitch, yaw, roll)
+    }
+
+    /// Crea la rotación que lleva +Z hacia `forward` y +Y lo más cerca posible de `up`
+    pub fn look_rotation(forward: Vec3, up: Vec3) -> Self {
+        let z = forward.normalize();
+        let mut x = up.cross(&z);
+        if x.length_squared() < 1e-12 {
+            // forward paralelo a up: elegir otro eje de referencia
+            let alt = if z.x.abs() < 0.9 { Vec3::unit_x() } else { Vec3::unit_z() };
+            x = alt.cross(&z);
+        }
+        let x = x.normalize();
+        let y = z.cross(&x);
+
+        Self::from_basis(x, y, z)
+    }
+
+    /// Crea una rotación desde una base ortonormal (columnas de la matriz de rotación)
+    pub fn from_basis(x: Vec3, y: Vec3, z: Vec3) -> Self {
+        Self::from_rotation_columns([[x.x, y.x, z.x], [x.y, y.y, z.y], [x.z, y.z, z.z]])
+    }
+
+    /// Extrae la rotación de la parte 3x3 de una matriz (se asume sin escala)
+    pub fn from_mat4(m: &Mat4) -> Self {
+        let r = [
+            [m.m[0][0], m.m[0][1], m.m[0][2]],
+            [m.m[1][0], m.m[1][1], m.m[1][2]],
+            [m.m[2][0], m.m[2][1], m.m[2][2]],
+        ];
+        Self::from_rotation_columns(r)
+    }
+
+    // Método de Shepperd: elige la diagonal dominante para evitar divisiones pequeñas
+    fn from_rotation_columns(r: [[f32; 3]; 3]) -> Self {
+        let trace = r[0][0] + r[1][1] + r[2][2];
+
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self::new(
+                (r[2][1] - r[1][2]) / s,
+                (r[0][2] - r[2][0]) / s,
+                (r[1][0] - r[0][1]) / s,
+                0.25 * s,
+            )
+        } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+            let s = (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt() * 2.0;
+            Self::new(
+                0.25 * s,
+                (r[0][1] + r[1][0]) / s,
+                (r[0][2] + r[2][0]) / s,
+                (r[2][1] - r[1][2]) / s,
+            )
+        } else if r[1][1] > r[2][2] {
+            let s = (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt() * 2.0;
+            Self::new(
+                (r[0][1] + r[1][0]) / s,
+                0.25 * s,
+                (r[1][2] + r[2][1]) / s,
+                (r[0][2] - r[2][0]) / s,
+            )
+        } else {
+            let s = (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt() * 2.0;
+            Self::new(
+                (r[0][2] + r[2][0]) / s,
+                (r[1][2] + r[2][1]) / s,
+                0.25 * s,
+                (r[1][0] - r[0][1]) / s,
+            )
+        };
+
+        q.normalize()
+    }
+
+    /// Convierte a matriz de rotación 4x4
+    pub fn to_mat4(self) -> Mat4 {
+        let Self { x, y, z, w } = self;
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, xz, yz) = (x * y, x * z, y * z);
+        let (wx, wy, wz) = (w * x, w * y, w * z);
+
+        Mat4::new([
+            [1.0 - 2.0 * (yy + zz), 2.0 * (xy - wz), 2.0 * (xz + wy), 0.0],
+            [2.0 * (xy + wz), 1.0 - 2.0 * (xx + zz), 2.0 * (yz - wx), 0.0],
+            [2.0 * (xz - wy), 2.0 * (yz + wx), 1.0 - 2.0 * (xx + yy), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Magnitud del cuaternión
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Normaliza el cuaternión (la identidad si es degenerado)
+    pub fn normalize(&self) -> Self {
+        let len = self.length();
+        if len > 0.0 {
+            Self::new(self.x / len, self.y / len, self.z / len, self.w / len)
+        } else {
+            Self::identity()
+        }
+    }
+
+    /// Producto punto
+    pub fn dot(&self, other: &Quat) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    /// Conjugado (rotación inversa si el cuaternión es unitario)
+    pub fn conjugate(&self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// Inverso general
+    pub fn inverse(&self) -> Self {
+        let len_sq = self.dot(self);
+        if len_sq > 0.0 {
+            let c = self.conjugate();
+            Self::new(c.x / len_sq, c.y / len_sq, c.z / len_sq, c.w / len_sq)
+        } else {
+            Self::identity()
+        }
+    }
+
+    /// Rota un vector
+    pub fn rotate(&self, v: Vec3) -> Vec3 {
+        // v' = v + 2w (q × v) + 2 q × (q × v)
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(&v) * 2.0;
+        v + t * self.w + q.cross(&t)
+    }
+
+    /// Dirección local +Z (adelante) en espacio mundo
+    pub fn forward(&self) -> Vec3 {
+        self.rotate(Vec3::unit_z())
+    }
+
+    /// Derecha en pantalla al mirar hacia `forward` (forward × up = -X local),
+    /// igual que el vector `s` de `Mat4::look_at`
+    pub fn right(&self) -> Vec3 {
+        self.rotate(-Vec3::unit_x())
+    }
+
+    /// Dirección local +Y en espacio mundo
+    pub fn up(&self) -> Vec3 {
+        self.rotate(Vec3::unit_y())
+    }
+
+    /// Ángulo (radianes) entre dos orientaciones
+    pub fn angle_to(&self, other: &Quat) -> f32 {
+        let d = self.normalize().dot(&other.normalize()).abs().min(1.0);
+        2.0 * d.acos()
+    }
+
+    /// Interpolación lineal normalizada por el camino más corto.
+    /// Más barata que `slerp`, con velocidad angular no constante
+    pub fn nlerp(&self, other: &Quat, t: f32) -> Self {
+        let other = if self.dot(other) < 0.0 { -*other } else { *other };
+        Self::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+            self.z + (other.z - self.z) * t,
+            self.w + (other.w - self.w) * t,
+        )
+        .normalize()
+    }
+
+    /// Interpolación esférica por el camino más corto (velocidad angular constante)
+    pub fn slerp(&self, other: &Quat, t: f32) -> Self {
+        let mut cos_theta = self.dot(other);
+        let mut other = *other;
+        if cos_theta < 0.0 {
+            other = -other;
+            cos_theta = -cos_theta;
+        }
+
+        // Muy cerca: slerp es numéricamente inestable, nlerp es equivalente
+        if cos_theta > 0.9995 {
+            return self.nlerp(&other, t);
+        }
+
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let a = ((1.0 - t) * theta).sin() / sin_theta;
+        let b = (t * theta).sin() / sin_theta;
+
+        Self::new(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b,
+        )
+    }
+
+    /// Logaritmo de un cuaternión unitario: (eje * ángulo/2, 0)
+    pub fn ln(self) -> Self {
+        let v = Vec3::new(self.x, self.y, self.z);
+        let sin_half = v.length();
+        if sin_half < 1e-7 {
+            return Self::new(0.0, 0.0, 0.0, 0.0);
+        }
+        let half = sin_half.atan2(self.w);
+        let v = v * (half / sin_half);
+        Self::new(v.x, v.y, v.z, 0.0)
+    }
+
+    /// Exponencial de un cuaternión puro (inversa de `ln`)
+    pub fn exp(self) -> Self {
+        let v = Vec3::new(self.x, self.y, self.z);
+        let half = v.length();
+        if half < 1e-7 {
+            return Self::identity();
+        }
+        let v = v * (half.sin() / half);
+        Self::new(v.x, v.y, v.z, half.cos())
+    }
+
+    /// Punto de control de `squad` para `self` entre sus vecinos
+    pub fn squad_control(&self, prev: &Quat, next: &Quat) -> Self {
+        let inv = self.conjugate();
+        let a = (inv * *next).ln();
+        let b = (inv * *prev).ln();
+        let sum = Self::new(a.x + b.x, a.y + b.y, a.z + b.z, 0.0);
+        (*self * Self::new(sum.x * -0.25, sum.y * -0.25, sum.z * -0.25, 0.0).exp()).normalize()
+    }
+
+    /// Interpolación esférica cuadrática entre `self` y `other`, con los puntos de
+    /// control `s0` y `s1` (ver `squad_control`): continua en velocidad angular
+    pub fn squad(&self, other: &Quat, s0: &Quat, s1: &Quat, t: f32) -> Self {
+        let a = self.slerp_direct(other, t);
+        let b = s0.slerp_direct(s1, t);
+        a.slerp_direct(&b, 2.0 * t * (1.0 - t))
+    }
+
+    /// Slerp sin elegir el camino más corto (lo necesita `squad`)
+    fn slerp_direct(&self, other: &Quat, t: f32) -> Self {
+        let cos_theta = self.dot(other).clamp(-1.0, 1.0);
+        if cos_theta.abs() > 0.9995 {
+            return Self::new(
+                self.x + (other.x - self.x) * t,
+                self.y + (other.y - self.y) * t,
+                self.z + (other.z - self.z) * t,
+                self.w + (other.w - self.w) * t,
+            )
+            .normalize();
+        }
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let a = ((1.0 - t) * theta).sin() / sin_theta;
+        let b = (t * theta).sin() / sin_theta;
+        Self::new(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b,
+        )
+    }
+
+    /// Integra una velocidad angular (rad/s, espacio mundo) durante `dt` segundos
+    pub fn integrate(&self, angular_velocity: Vec3, dt: f32) -> Self {
+        let speed = angular_velocity.length();
+        if speed * dt < 1e-9 {
+            return *self;
+        }
+        let dq = Self::from_axis_angle(angular_velocity / speed, speed * dt);
+        (dq * *self).normalize()
+    }
+}
+
+/// Composición de rotaciones: (a * b) aplica primero b y luego a
+impl Mul for Quat {
+    type Output = Self;
+
+    fn mul(self, o: Self) -> Self {
+        Self::new(
+            self.w * o.x + self.x * o.w + self.y * o.z - self.z * o.y,
+            self.w * o.y - self.x * o.z + self.y * o.w + self.z * o.x,
+            self.w * o.z + self.x * o.y - self.y * o.x + self.z * o.w,
+            self.w * o.w - self.x * o.x - self.y * o.y - self.z * o.z,
+        )
+    }
+}
+
+/// Rotación de un vector
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    fn mul(self, v: Vec3) -> Vec3 {
+        self.rotate(v)
+    }
+}
+
+impl Neg for Quat {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, -self.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    const EPS: f32 = 1e-4;
+
+    fn assert_vec_eq(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < EPS, "{:?} != {:?}", a, b);
+    }
+
+    /// Dos cuaterniones representan la misma rotación (q y -q son equivalentes)
+    fn assert_same_rotation(a: Quat, b: Quat) {
+        assert!((a.dot(&b).abs() - 1.0).abs() < EPS, "{:?} != {:?}", a, b);
+    }
+
+    fn assert_mat_eq(a: &Mat4, b: &Mat4) {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((a.m[i][j] - b.m[i][j]).abs() < EPS, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_identity_rotation() {
+        let v = Vec3::new(1.0, -2.0, 3.0);
+        assert_vec_eq(Quat::identity().rotate(v), v);
+    }
+
+    #[test]
+    fn test_axis_angle_rotation() {
+        let q = Quat::from_axis_angle(Vec3::unit_y(), FRAC_PI_2);
+        // Igual que Mat4::rotation_y: +Z gira hacia +X
+        assert_vec_eq(q * Vec3::unit_z(), Vec3::unit_x());
+        assert_vec_eq(q * Vec3::unit_x(), -Vec3::unit_z());
+    }
+
+    #[test]
+    fn test_axis_angle_roundtrip() {
+        let axis = Vec3::new(1.0, 2.0, -0.5).normalize();
+        let (a, angle) = Quat::from_axis_angle(axis, 1.2).to_axis_angle();
+        assert_vec_eq(a, axis);
+        assert!((angle - 1.2).abs() < EPS);
+
+        let (_, zero) = Quat::identity().to_axis_angle();
+        assert_eq!(zero, 0.0);
+    }
+
+    #[test]
+    fn test_matches_mat4_rotations() {
+        for angle in [-2.0, -0.3, 0.0, 0.7, PI] {
+            assert_mat_eq(
+                &Quat::from_axis_angle(Vec3::unit_x(), angle).to_mat4(),
+                &Mat4::rotation_x(angle),
+            );
+            assert_mat_eq(
+                &Quat::from_axis_angle(Vec3::unit_y(), angle).to_mat4(),
+                &Mat4::rotation_y(angle),
+            );
+            assert_mat_eq(
+                &Quat::from_axis_angle(Vec3::unit_z(), angle).to_mat4(),
+                &Mat4::rotation_z(angle),
+            );
+        }
+
+        let axis = Vec3::new(0.3, -1.0, 0.8);
+        assert_mat_eq(
+            &Quat::from_axis_angle(axis, 2.1).to_mat4(),
+            &Mat4::rotation_axis(axis, 2.1),
+        );
+    }
+
+    #[test]
+    fn test_euler_matches_transform_order() {
+        let (pitch, yaw, roll) = (0.4, -1.1, 0.9);
+        let q = Quat::from_euler(pitch, yaw, roll);
+        let m = Mat4::rotation_z(roll) * Mat4::rotation_y(yaw) * Mat4::rotation_x(pitch);
+        assert_mat_eq(&q.to_mat4(), &m);
+    }
+
+    #[test]
+    fn test_euler_roundtrip() {
+        let angles = Vec3::new(0.3, -0.8, 1.4);
+        let back = Quat::from_euler(angles.x, angles.y, angles.z).to_euler();
+        assert_vec_eq(back, angles);
+    }
+
+    #[test]
+    fn test_euler_gimbal_lock_is_stable() {
+        // yaw = 90°: pitch y roll se vuelven el mismo eje, pero la rotación debe conservarse
+        let q = Quat::from_euler(0.5, FRAC_PI_2, 0.2);
+        let e = q.to_euler();
+        let back = Quat::from_euler(e.x, e.y, e.z);
+        assert_same_rotation(q, back);
+    }
+
+    #[test]
+    fn test_matrix_roundtrip() {
+        let q = Quat::from_axis_angle(Vec3::new(-0.2, 0.9, 0.4), 2.8);
+        assert_same_rotation(Quat::from_mat4(&q.to_mat4()), q);
+
+        // Rotación de 180° (traza negativa) ejercita las otras ramas
+        for axis in [Vec3::unit_x(), Vec3::unit_y(), Vec3::unit_z()] {
+            let q = Quat::from_axis_angle(axis, PI);
+            assert_same_rotation(Quat::from_mat4(&q.to_mat4()), q);
+        }
+    }
+
+    #[test]
+    fn test_multiplication_composes() {
+        let a = Quat::from_axis_angle(Vec3::unit_y(), 0.7);
+        let b = Quat::from_axis_angle(Vec3::unit_x(), -1.3);
+        let v = Vec3::new(0.5, 1.0, -2.0);
+
+        assert_vec_eq((a * b) * v, a * (b * v));
+        assert_mat_eq(&(a * b).to_mat4(), &(a.to_mat4() * b.to_mat4()));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let q = Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 0.9);
+        assert_same_rotation(q * q.inverse(), Quat::identity());
+        assert_same_rotation(q.conjugate(), q.inverse());
+
+        let v = Vec3::new(3.0, -1.0, 2.0);
+        assert_vec_eq(q.inverse() * (q * v), v);
+    }
+
+    #[test]
+    fn test_rotation_preserves_length() {
+        let q = Quat::from_euler(1.0, 2.0, 3.0);
+        let v = Vec3::new(4.0, -5.0, 6.0);
+        assert!(((q * v).length() - v.length()).abs() < EPS);
+    }
+
+    #[test]
+    fn test_slerp_endpoints_and_midpoint() {
+        let a = Quat::identity();
+        let b = Quat::from_axis_angle(Vec3::unit_y(), FRAC_PI_2);
+
+        assert_same_rotation(a.slerp(&b, 0.0), a);
+        assert_same_rotation(a.slerp(&b, 1.0), b);
+        assert_same_rotation(a.slerp(&b, 0.5), Quat::from_axis_angle(Vec3::unit_y(), FRAC_PI_4));
+    }
+
+    #[test]
+    fn test_slerp_constant_speed() {
+        let a = Quat::from_axis_angle(Vec3::unit_z(), 0.1);
+        let b = Quat::from_axis_angle(Vec3::unit_z(), 2.5);
+
+        let q1 = a.slerp(&b, 0.25);
+        let q2 = a.slerp(&b, 0.5);
+        assert!((a.angle_to(&q1) - q1.angle_to(&q2)).abs() < EPS);
+    }
+
+    #[test]
+    fn test_slerp_takes_shortest_path() {
+        let a = Quat::from_axis_angle(Vec3::unit_y(), 0.2);
+        let b = -Quat::from_axis_angle(Vec3::unit_y(), 0.6);
+        let mid = a.slerp(&b, 0.5);
+        assert_same_rotation(mid, Quat::from_axis_angle(Vec3::unit_y(), 0.4));
+    }
+
+    #[test]
+    fn test_nlerp_is_normalized_and_matches_endpoints() {
+        let a = Quat::from_euler(0.2, 0.4, 0.0);
+        let b = Quat::from_euler(-1.0, 2.0, 0.5);
+
+        for i in 0..=10 {
+            let q = a.nlerp(&b, i as f32 / 10.0);
+            assert!((q.length() - 1.0).abs() < EPS);
+        }
+        assert_same_rotation(a.nlerp(&b, 0.0), a);
+        assert_same_rotation(a.nlerp(&b, 1.0), b);
+    }
+
+    #[test]
+    fn test_look_rotation() {
+        let q = Quat::look_rotation(Vec3::unit_z(), Vec3::unit_y());
+        assert_same_rotation(q, Quat::identity());
+
+        let dir = Vec3::new(1.0, 0.5, -2.0).normalize();
+        let q = Quat::look_rotation(dir, Vec3::unit_y());
+        assert_vec_eq(q.forward(), dir);
+        assert!(q.right().dot(&Vec3::unit_y()).abs() < EPS);
+        assert!(q.up().y > 0.0);
+        assert_vec_eq(q.right(), dir.cross(&q.up()));
+    }
+
+    #[test]
+    fn test_look_rotation_straight_up() {
+        // forward paralelo a up no debe producir NaN
+        let q = Quat::look_rotation(Vec3::unit_y(), Vec3::unit_y());
+        assert!(q.x.is_finite() && q.y.is_finite() && q.z.is_finite() && q.w.is_finite());
+        assert_vec_eq(q.forward(), Vec3::unit_y());
+    }
+
+    #[test]
+    fn test_look_rotation_matches_freecam_angles() {
+        // La cámara libre usa forward = Ry(yaw) * Rx(pitch) * +Z
+        let (yaw, pitch) = (0.8_f32, 0.3_f32);
+        let forward = Vec3::new(
+            yaw.sin() * pitch.cos(),
+            -pitch.sin(),
+            yaw.cos() * pitch.cos(),
+        );
+        let q = Quat::from_euler(pitch, yaw, 0.0);
+        let q_yx = Quat::from_axis_angle(Vec3::unit_y(), yaw) * Quat::from_axis_angle(Vec3::unit_x(), pitch);
+        assert_vec_eq(q_yx.forward(), forward);
+        assert_same_rotation(Quat::look_rotation(forward, Vec3::unit_y()), q_yx);
+        // El orden Z*Y*X con roll = 0 coincide con Y*X
+        assert_same_rotation(q, q_yx);
+    }
+
+    #[test]
+    fn test_integrate_angular_velocity() {
+        // 1 rad/s alrededor de Y durante π/2 s = 90°
+        let omega = Vec3::new(0.0, 1.0, 0.0);
+        let mut q = Quat::identity();
+        let steps = 100;
+        for _ in 0..steps {
+            q = q.integrate(omega, FRAC_PI_2 / steps as f32);
+        }
+        assert_same_rotation(q, Quat::from_axis_angle(Vec3::unit_y(), FRAC_PI_2));
+        assert!((q.length() - 1.0).abs() < EPS);
+    }
+
+    #[test]
+    fn test_integrate_is_world_space() {
+        // Tras girar 90° en Y, una velocidad en X mundial sigue siendo eje X mundial
+        let start = Quat::from_axis_angle(Vec3::unit_y(), FRAC_PI_2);
+        let q = start.integrate(Vec3::new(0.5, 0.0, 0.0), 1.0);
+        let expected = Quat::from_axis_angle(Vec3::unit_x(), 0.5) * start;
+        assert_same_rotation(q, expected);
+
+        assert_eq!(start.integrate(Vec3::zero(), 1.0), start);
+    }
+
+    #[test]
+    fn test_ln_exp_roundtrip() {
+        let q = Quat::from_axis_angle(Vec3::new(1.0, 2.0, -1.0).normalize(), 1.3);
+        let back = q.ln().exp();
+        assert!(q.angle_to(&back) < 1e-5);
+        assert!((q.ln().w).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_squad_endpoints_and_degenerates_to_slerp() {
+        let a = Quat::from_axis_angle(Vec3::unit_y(), 0.2);
+        let b = Quat::from_axis_angle(Vec3::unit_y(), 1.4);
+        let (s0, s1) = (a, b);
+        assert!(a.squad(&b, &s0, &s1, 0.0).angle_to(&a) < 1e-5);
+        assert!(a.squad(&b, &s0, &s1, 1.0).angle_to(&b) < 1e-5);
+
+        // Rotaciones sobre un mismo eje a paso constante: los controles no curvan nada
+        let prev = Quat::from_axis_angle(Vec3::unit_y(), -1.0);
+        let next = Quat::from_axis_angle(Vec3::unit_y(), 2.6);
+        let s0 = a.squad_control(&prev, &b);
+        let s1 = b.squad_control(&a, &next);
+        let mid = a.squad(&b, &s0, &s1, 0.5);
+        assert!(mid.angle_to(&a.slerp(&b, 0.5)) < 1e-4);
+    }
+}
@@ -1,5 +1,9 @@
+// API matemática general: no todo lo que expone lo usa el binario
+#![allow(dead_code)]
+
 use super::matrix::Mat4;
-use super::vector::Vec3;
+use super::vector::{Vec3, DVec3};
+use super::quaternion::Quat;
 
 /// Crea una matriz de transformación completa (TRS: Translation, Rotation, Scale)
 pub fn create_transform_matrix(
@@ -17,6 +21,14 @@ pub fn create_transform_matrix(
     t * rz * ry * rx * s
 }
 
+/// Igual que `create_transform_matrix` pero con la rotación dada como cuaternión
+pub fn create_transform_matrix_quat(translation: Vec3, rotation: Quat, scale: Vec3) -> Mat4 {
+    let t = Mat4::translation(translation.x, translation.y, translation.z);
+    let s = Mat4::scale(scale.x, scale.y, scale.z);
+
+    t * rotation.to_mat4() * s
+}
+
 /// Crea una matriz Model-View-Projection
 pub fn create_mvp_matrix(model: Mat4, view: Mat4, projection: Mat4) -> Mat4 {
     projection * view * model
@@ -49,6 +61,26 @@ pub fn world_to_screen(
     Some((screen_x, screen_y, screen_z))
 }
 
+/// Inversa de `world_to_screen`: lleva un píxel y una profundidad NDC de vuelta
+/// al mundo usando la inversa de la view-projection
+pub fn screen_to_world(
+    screen_x: f32,
+    screen_y: f32,
+    ndc_z: f32,
+    inv_view_proj: &Mat4,
+    screen_width: f32,
+    screen_height: f32,
+) -> Option<Vec3> {
+    let ndc_x = screen_x / screen_width * 2.0 - 1.0;
+    let ndc_y = 1.0 - screen_y / screen_height * 2.0;
+
+    let world = inv_view_proj.mul_vec4(super::vector::Vec4::new(ndc_x, ndc_y, ndc_z, 1.0));
+    if world.w.abs() < 1e-12 {
+        return None;
+    }
+    Some(world.to_vec3())
+}
+
 /// Convierte grados a radianes
 pub fn deg_to_rad(degrees: f32) -> f32 {
     degrees * std::f32::consts::PI / 180.0
@@ -129,6 +161,24 @@ pub fn point_in_triangle(
     u >= 0.0 && v >= 0.0 && w >= 0.0
 }
 
+/// Punto de una spline Catmull-Rom entre `p1` y `p2` (u en [0, 1]);
+/// `p0` y `p3` son los vecinos que definen las tangentes
+pub fn catmull_rom(p0: DVec3, p1: DVec3, p2: DVec3, p3: DVec3, u: f64) -> DVec3 {
+    let u2 = u * u;
+    let u3 = u2 * u;
+    (p1 * 2.0
+        + (p2 - p0) * u
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * u2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * u3)
+        * 0.5
+}
+
+/// Punto de una Bézier cúbica de `p0` a `p1` con puntos de control `c0` y `c1`
+pub fn bezier(p0: DVec3, c0: DVec3, c1: DVec3, p1: DVec3, u: f64) -> DVec3 {
+    let v = 1.0 - u;
+    p0 * (v * v * v) + c0 * (3.0 * v * v * u) + c1 * (3.0 * v * u * u) + p1 * (u * u * u)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,6 +196,34 @@ mod tests {
         assert_eq!(lerp(0.0, 10.0, 1.0), 10.0);
     }
 
+    #[test]
+    fn test_screen_to_world_inverts_world_to_screen() {
+        let view = Mat4::look_at(Vec3::zero(), Vec3::new(0.3, -0.2, 1.0), Vec3::unit_y());
+        let view_proj = Mat4::perspective_reversed_z(1.0, 16.0 / 9.0, 0.1, 1000.0) * view;
+        let inv = view_proj.inverse().unwrap();
+
+        let point = Vec3::new(4.0, -1.5, 20.0);
+        let (x, y, z) = world_to_screen(point, &view_proj, 1280.0, 720.0).unwrap();
+        let back = screen_to_world(x, y, z, &inv, 1280.0, 720.0).unwrap();
+        assert!((back - point).length() < 0.01);
+    }
+
+    #[test]
+    fn test_transform_matrix_quat_matches_euler() {
+        let t = Vec3::new(1.0, -2.0, 3.0);
+        let r = Vec3::new(0.3, 1.2, -0.7);
+        let s = Vec3::new(2.0, 0.5, 1.5);
+
+        let euler = create_transform_matrix(t, r, s);
+        let quat = create_transform_matrix_quat(t, Quat::from_euler(r.x, r.y, r.z), s);
+
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((euler.m[i][j] - quat.m[i][j]).abs() < 0.0001);
+            }
+        }
+    }
+
     #[test]
     fn test_barycentric() {
         let v0 = (0.0, 0.0);
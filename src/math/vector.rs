@@ -1,7 +1,7 @@
 use std::ops::{Add, Sub, Mul, Div, Neg};
 
 /// Vector 3D (x, y, z)
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Vec3 {
     pub x: f32,
     pub y: f32,
@@ -136,6 +136,140 @@ impl Neg for Vec3 {
     }
 }
 
+/// Vector 3D en doble precisión para posiciones en el mundo.
+/// Se convierte a `Vec3` solo después de restar la posición de la cámara
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DVec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl DVec3 {
+    /// Crea un nuevo vector 3D de doble precisión
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Vector cero
+    pub fn zero() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+
+    /// Promueve un `Vec3` a doble precisión
+    pub fn from_vec3(v: Vec3) -> Self {
+        Self::new(v.x as f64, v.y as f64, v.z as f64)
+    }
+
+    /// Convierte a `Vec3` (pierde precisión: usar solo con coordenadas relativas)
+    pub fn to_vec3(self) -> Vec3 {
+        Vec3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+
+    /// Magnitud del vector
+    pub fn length(&self) -> f64 {
+        self.length_squared().sqrt()
+    }
+
+    /// Magnitud al cuadrado
+    pub fn length_squared(&self) -> f64 {
+        self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
+    /// Normaliza el vector
+    pub fn normalize(&self) -> Self {
+        let len = self.length();
+        if len > 0.0 {
+            Self::new(self.x / len, self.y / len, self.z / len)
+        } else {
+            *self
+        }
+    }
+
+    /// Producto punto
+    pub fn dot(&self, other: &DVec3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Producto cruz
+    pub fn cross(&self, other: &DVec3) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Interpolación lineal entre dos vectores
+    pub fn lerp(&self, other: &DVec3, t: f64) -> Self {
+        Self::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+            self.z + (other.z - self.z) * t,
+        )
+    }
+
+    /// Distancia entre dos puntos
+    pub fn distance(&self, other: &DVec3) -> f64 {
+        (*self - *other).length()
+    }
+
+    /// Distancia al cuadrado
+    pub fn distance_squared(&self, other: &DVec3) -> f64 {
+        (*self - *other).length_squared()
+    }
+}
+
+impl Add for DVec3 {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for DVec3 {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f64> for DVec3 {
+    type Output = Self;
+    fn mul(self, scalar: f64) -> Self {
+        Self::new(self.x * scalar, self.y * scalar, self.z * scalar)
+    }
+}
+
+impl Mul<DVec3> for f64 {
+    type Output = DVec3;
+    fn mul(self, vec: DVec3) -> DVec3 {
+        DVec3::new(vec.x * self, vec.y * self, vec.z * self)
+    }
+}
+
+impl Div<f64> for DVec3 {
+    type Output = Self;
+    fn div(self, scalar: f64) -> Self {
+        Self::new(self.x / scalar, self.y / scalar, self.z / scalar)
+    }
+}
+
+impl Neg for DVec3 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// Desplazar una posición de mundo por un offset en f32
+impl Add<Vec3> for DVec3 {
+    type Output = Self;
+    fn add(self, offset: Vec3) -> Self {
+        self + DVec3::from_vec3(offset)
+    }
+}
+
 /// Vector 4D (x, y, z, w) - usado para transformaciones homogéneas
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vec4 {
@@ -162,7 +296,7 @@ impl Vec4 {
     }
 
     /// Convierte a Vec3 dividiendo por w (perspectiva)
-    pub fn to_vec3(&self) -> Vec3 {
+    pub fn to_vec3(self) -> Vec3 {
         if self.w != 0.0 {
             Vec3::new(self.x / self.w, self.y / self.w, self.z / self.w)
         } else {
@@ -171,7 +305,7 @@ impl Vec4 {
     }
 
     /// Convierte a Vec3 sin dividir por w
-    pub fn to_vec3_no_divide(&self) -> Vec3 {
+    pub fn to_vec3_no_divide(self) -> Vec3 {
         Vec3::new(self.x, self.y, self.z)
     }
 }
@@ -234,4 +368,29 @@ mod tests {
         let normalized = v.normalize();
         assert!((normalized.length() - 1.0).abs() < 0.0001);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_dvec3_operations() {
+        let a = DVec3::new(1.0, 2.0, 3.0);
+        let b = DVec3::new(4.0, 5.0, 6.0);
+        assert_eq!(a + b, DVec3::new(5.0, 7.0, 9.0));
+        assert_eq!(a.dot(&b), 32.0);
+        assert_eq!(a.cross(&b), DVec3::new(-3.0, 6.0, -3.0));
+        assert!((b.normalize().length() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dvec3_camera_relative_precision() {
+        // A 1e9 unidades del origen f32 no distingue 0.25 unidades
+        let far = 1.0e9;
+        let body = DVec3::new(far + 0.25, 0.0, far);
+        let camera = DVec3::new(far, 0.0, far);
+
+        let naive = body.to_vec3() - camera.to_vec3();
+        assert_eq!(naive.x, 0.0);
+
+        // Restando en f64 antes de convertir se conserva el detalle
+        let relative = (body - camera).to_vec3();
+        assert_eq!(relative.x, 0.25);
+    }
+}
@@ -19,6 +19,7 @@ impl Mesh {
         }
     }
 
+    #[allow(dead_code)]
     pub fn translate(&mut self, offset: Vec3) {
         for v in &mut self.vertices {
             v.x += offset.x;
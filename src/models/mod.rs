@@ -1,9 +1,11 @@
 pub mod mesh;
 pub mod obj_loader;
+pub mod ring;
 pub mod sphere;
 pub mod spaceship;
 
 pub use mesh::*;
 pub use obj_loader::*;
+pub use ring::*;
 pub use sphere::*;
 pub use spaceship::*;
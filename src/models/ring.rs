@@ -0,0 +1,168 @@
+use crate::math::{Vec3, Vec4, Mat4, smooth_step};
+use crate::framebuffer::lerp_color;
+use super::mesh::Mesh;
+
+const PROFILE_SIZE: usize = 256;
+const RING_HIGHLIGHT: u32 = 0xFFFFF4E0;
+
+/// Genera un anillo plano (corona circular) en el plano XZ
+pub fn generate_ring(inner_radius: f32, outer_radius: f32, segments: usize) -> Mesh {
+    let segments = segments.max(3);
+    let mut vertices = Vec::with_capacity(segments * 2);
+    let mut faces = Vec::with_capacity(segments * 2);
+
+    for j in 0..segments {
+        let phi = j as f32 * std::f32::consts::TAU / segments as f32;
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        vertices.push(Vec3::new(inner_radius * cos_phi, 0.0, inner_radius * sin_phi));
+        vertices.push(Vec3::new(outer_radius * cos_phi, 0.0, outer_radius * sin_phi));
+    }
+
+    for j in 0..segments {
+        let next = (j + 1) % segments;
+        let (i0, o0, i1, o1) = (2 * j, 2 * j + 1, 2 * next, 2 * next + 1);
+        faces.push((i0, o0, o1));
+        faces.push((i0, o1, i1));
+    }
+
+    Mesh::new(vertices, faces)
+}
+
+/// Sistema de anillos de un planeta. Las distancias están en radios del planeta
+#[derive(Clone)]
+pub struct Ring {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub tilt: f32,          // inclinación del plano sobre el eje X (radianes)
+    pub mesh: Mesh,
+    pub profile: Vec<u32>,  // color de adentro hacia afuera; el alfa es la densidad
+}
+
+impl Ring {
+    /// Anillo con bandas al estilo de Saturno: zona interna tenue, una división
+    /// oscura y bordes que se desvanecen
+    pub fn new(inner_radius: f32, outer_radius: f32, segments: usize, tilt: f32, color: u32) -> Self {
+        let profile = (0..PROFILE_SIZE)
+            .map(|i| {
+                let t = i as f32 / (PROFILE_SIZE - 1) as f32;
+                let bands = 0.6 + 0.2 * (t * 37.0).sin() + 0.1 * (t * 91.0 + 1.3).sin() + 0.05 * (t * 173.0).sin();
+                let inner_faint = 0.35 + 0.65 * smooth_step((t - 0.1) / 0.15);
+                let division = 1.0 - 0.92 * smooth_step(1.0 - ((t - 0.65) / 0.03).abs());
+                let edges = smooth_step(t / 0.03) * smooth_step((1.0 - t) / 0.03);
+                let density = (bands * inner_faint * division * edges).clamp(0.0, 0.9);
+
+                let shade = lerp_color(color, RING_HIGHLIGHT, 0.25 + 0.25 * (t * 53.0).sin());
+                ((density * 255.0) as u32) << 24 | (shade & 0x00FFFFFF)
+            })
+            .collect();
+        Self::with_profile(inner_radius, outer_radius, segments, tilt, profile)
+    }
+
+    /// Anillo con un perfil radial propio (por ejemplo, leído de una imagen)
+    pub fn with_profile(inner_radius: f32, outer_radius: f32, segments: usize, tilt: f32, profile: Vec<u32>) -> Self {
+        Self {
+            inner_radius,
+            outer_radius,
+            tilt,
+            mesh: generate_ring(inner_radius, outer_radius, segments),
+            profile,
+        }
+    }
+
+    /// Rotación del plano del anillo (el anillo se genera en XZ)
+    pub fn rotation(&self) -> Mat4 {
+        Mat4::rotation_x(self.tilt)
+    }
+
+    /// Normal del plano del anillo
+    pub fn normal(&self) -> Vec3 {
+        self.rotation().mul_vec4(Vec4::from_direction(Vec3::unit_y())).to_vec3_no_divide()
+    }
+
+    /// Color y opacidad a `radius` radios del centro, o `None` fuera del anillo
+    pub fn sample(&self, radius: f32) -> Option<(u32, f32)> {
+        if radius < self.inner_radius || radius > self.outer_radius || self.profile.is_empty() {
+            return None;
+        }
+        let x = (radius - self.inner_radius) / (self.outer_radius - self.inner_radius) * (self.profile.len() - 1) as f32;
+        let i = (x as usize).min(self.profile.len() - 1);
+        let j = (i + 1).min(self.profile.len() - 1);
+        let f = x - i as f32;
+
+        let (a, b) = (self.profile[i], self.profile[j]);
+        let alpha = ((a >> 24) as f32 + ((b >> 24) as f32 - (a >> 24) as f32) * f) / 255.0;
+        Some((lerp_color(a, b, f), alpha))
+    }
+
+    /// Cuánto oscurece el anillo a `point` (relativo al planeta, en radios) con la
+    /// luz llegando desde la dirección `to_light`
+    pub fn shadow_at(&self, point: Vec3, to_light: Vec3) -> f32 {
+        let normal = self.normal();
+        let facing = to_light.dot(&normal);
+        if facing.abs() < 1e-6 {
+            return 0.0;
+        }
+        // Rayo hacia la luz contra el plano del anillo
+        let t = -point.dot(&normal) / facing;
+        if t <= 0.0 {
+            return 0.0;
+        }
+        let hit = point + to_light * t;
+        self.sample(hit.length()).map_or(0.0, |(_, alpha)| alpha)
+    }
+}
+
+/// Verdadero si `point` (relativo al centro de una esfera de radio `radius`)
+/// queda en la sombra de la esfera con la luz llegando desde `to_light`
+pub fn in_sphere_shadow(point: Vec3, to_light: Vec3, radius: f32) -> bool {
+    let t = -point.dot(&to_light);
+    t > 0.0 && (point + to_light * t).length() < radius
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_mesh() {
+        let mesh = generate_ring(1.5, 2.5, 32);
+        assert_eq!(mesh.vertices.len(), 64);
+        assert_eq!(mesh.faces.len(), 64);
+        for (i, v) in mesh.vertices.iter().enumerate() {
+            let expected = if i % 2 == 0 { 1.5 } else { 2.5 };
+            assert!((v.length() - expected).abs() < 1e-5);
+            assert_eq!(v.y, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_profile_has_gap_and_faded_edges() {
+        let ring = Ring::new(1.4, 2.4, 64, 0.0, 0xFFD8C8A0);
+        assert!(ring.sample(1.3).is_none());
+        assert!(ring.sample(2.5).is_none());
+
+        let alpha = |t: f32| ring.sample(1.4 + t).unwrap().1;
+        assert!(alpha(0.0) < 0.05);            // borde interno
+        assert!(alpha(0.65) < alpha(0.45));    // división oscura
+        assert!(alpha(0.45) > 0.3);
+        for i in 0..=100 {
+            let (_, a) = ring.sample(1.4 + i as f32 / 100.0).unwrap();
+            assert!((0.0..=1.0).contains(&a));
+        }
+    }
+
+    #[test]
+    fn test_shadows() {
+        let ring = Ring::new(1.4, 2.4, 64, 0.0, 0xFFD8C8A0);
+        // Luz desde arriba en diagonal: el punto bajo el anillo queda a la sombra
+        let to_light = Vec3::new(1.8, 1.0, 0.0).normalize();
+        assert!(ring.shadow_at(Vec3::new(0.0, -1.0, 0.0), to_light) > 0.3);
+        // Con la luz de frente al ecuador no hay sombra del anillo
+        assert_eq!(ring.shadow_at(Vec3::new(1.0, 0.0, 0.0), Vec3::unit_x()), 0.0);
+
+        // El planeta hace sombra sobre el lado opuesto del anillo
+        assert!(in_sphere_shadow(Vec3::new(-2.0, 0.0, 0.0), Vec3::unit_x(), 1.0));
+        assert!(!in_sphere_shadow(Vec3::new(2.0, 0.0, 0.0), Vec3::unit_x(), 1.0));
+        assert!(!in_sphere_shadow(Vec3::new(0.0, 0.0, 2.0), Vec3::unit_x(), 1.0));
+    }
+}
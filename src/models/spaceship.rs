@@ -1,37 +1,307 @@
-use crate::math::{Vec3, Mat4};
+use crate::math::{Vec3, DVec3, Mat4, Quat};
+use crate::physics::{Bvh, CrashSite, Ray, RayHit};
+use super::mesh::Mesh;
 
+/// Nave controlada por el jugador: cuerpo rígido con empuje e inercia.
+/// Ejes locales: +Z adelante, +Y arriba (ver `Quat::look_rotation`)
 pub struct SpaceShip {
-    pub position: Vec3,
-    pub yaw: f32,
-    pub pitch: f32,
+    pub position: DVec3,
+    pub velocity: DVec3,
+    pub orientation: Quat,
+    pub angular_velocity: Vec3,  // rad/s, espacio mundo
+    pub mass: f32,
+    pub moment_of_inertia: f32,  // inercia escalar (nave aproximada como esfera)
+    pub max_thrust: f32,         // fuerza máxima por eje
+    pub max_torque: f32,         // torque máximo por eje
+    pub boost: f32,              // multiplicador de empuje con SHIFT
+    pub flight_assist: bool,     // amortigua traslación y rotación sin entrada
+    pub crashed: Option<CrashSite>,
     pub scale: f32,
-    pub mesh: crate::models::Mesh,
+    pub mesh: Mesh,
+    pub collider: Bvh,           // malla en ejes de la nave (ya girada y escalada)
+    pub seat: Vec3,              // asiento del piloto, en ejes de la nave
+    thrust_input: Vec3,          // [-1, 1] por eje local
+    torque_input: Vec3,          // [-1, 1] por eje local (pitch, yaw, roll)
 }
 
 impl SpaceShip {
-    pub fn new(mesh: crate::models::Mesh) -> Self {
+    pub fn new(mesh: Mesh) -> Self {
+        let scale = 0.4; // ⚠️ tamaño reducido para que no tape todo
+        let collider = Bvh::build_transformed(&mesh, &mesh_matrix(scale));
+
         Self {
-            position: Vec3::new(0.0, 0.0, 0.0),
-            yaw: 0.0,
-            pitch: 0.0,
-            scale: 0.4, // ⚠️ tamaño reducido para que no tape todo
+            position: DVec3::zero(),
+            velocity: DVec3::zero(),
+            orientation: Quat::identity(),
+            angular_velocity: Vec3::zero(),
+            mass: 1.0,
+            moment_of_inertia: 1.0,
+            max_thrust: 20.0,
+            max_torque: 6.0,
+            boost: 3.0,
+            flight_assist: true,
+            crashed: None,
+            scale,
+            seat: seat_in(&collider),
+            collider,
             mesh,
+            thrust_input: Vec3::zero(),
+            torque_input: Vec3::zero(),
+        }
+    }
+
+    /// Cambia la escala y reconstruye el colisionador
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+        self.rebuild_collider();
+    }
+
+    fn rebuild_collider(&mut self) {
+        self.collider = Bvh::build_transformed(&self.mesh, &mesh_matrix(self.scale));
+        self.seat = seat_in(&self.collider);
+    }
+
+    // ----------------------------
+    // DIRECCIONES
+    // ----------------------------
+    pub fn forward(&self) -> Vec3 {
+        self.orientation.forward()
+    }
+
+    pub fn right(&self) -> Vec3 {
+        self.orientation.right()
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.orientation.up()
+    }
+
+    // ----------------------------
+    // CONTROLES
+    // ----------------------------
+    /// Empuje deseado en ejes locales, cada componente en [-1, 1]
+    /// (puede exceder 1 con boost)
+    pub fn set_thrust(&mut self, local: Vec3) {
+        self.thrust_input = local;
+    }
+
+    /// Torque deseado en ejes locales (pitch, yaw, roll), cada componente en [-1, 1]
+    pub fn set_torque(&mut self, local: Vec3) {
+        self.torque_input = Vec3::new(
+            local.x.clamp(-1.0, 1.0),
+            local.y.clamp(-1.0, 1.0),
+            local.z.clamp(-1.0, 1.0),
+        );
+    }
+
+    /// Integra un paso de física (Euler semi-implícito).
+    /// `gravity` es la aceleración externa en el punto actual de la nave
+    pub fn update(&mut self, dt: f32, gravity: DVec3) {
+        if self.crashed.is_some() {
+            return;
+        }
+
+        // ---------- traslación ----------
+        let mut local_thrust = self.thrust_input * self.max_thrust;
+
+        if self.flight_assist {
+            // En los ejes sin entrada, contra-empuje para frenar la deriva
+            let local_vel = self.orientation.conjugate().rotate(self.velocity.to_vec3());
+            let brake = |input: f32, vel: f32| {
+                if input == 0.0 {
+                    (-vel * self.mass / dt).clamp(-self.max_thrust, self.max_thrust)
+                } else {
+                    0.0
+                }
+            };
+            local_thrust = local_thrust + Vec3::new(
+                brake(self.thrust_input.x, local_vel.x),
+                brake(self.thrust_input.y, local_vel.y),
+                brake(self.thrust_input.z, local_vel.z),
+            );
+        }
+
+        let accel = self.orientation.rotate(local_thrust) / self.mass;
+        self.velocity = self.velocity + gravity * dt as f64 + accel * dt;
+        self.position = self.position + self.velocity * dt as f64;
+
+        // ---------- rotación ----------
+        let mut local_torque = self.torque_input * self.max_torque;
+
+        if self.flight_assist {
+            let local_omega = self.orientation.conjugate().rotate(self.angular_velocity);
+            let brake = |input: f32, omega: f32| {
+                if input == 0.0 {
+                    (-omega * self.moment_of_inertia / dt).clamp(-self.max_torque, self.max_torque)
+                } else {
+                    0.0
+                }
+            };
+            local_torque = local_torque + Vec3::new(
+                brake(self.torque_input.x, local_omega.x),
+                brake(self.torque_input.y, local_omega.y),
+                brake(self.torque_input.z, local_omega.z),
+            );
+        }
+
+        let angular_accel = self.orientation.rotate(local_torque) / self.moment_of_inertia;
+        self.angular_velocity = self.angular_velocity + angular_accel * dt;
+        self.orientation = self.orientation.integrate(self.angular_velocity, dt);
+    }
+
+    /// Detiene la nave por completo (p. ej. al terminar un warp)
+    pub fn halt(&mut self) {
+        self.velocity = DVec3::zero();
+        self.angular_velocity = Vec3::zero();
+    }
+
+    /// Radio de la esfera que envuelve la malla escalada (para colisiones)
+    pub fn bounding_radius(&self) -> f32 {
+        self.mesh
+            .vertices
+            .iter()
+            .map(|v| v.length())
+            .fold(0.0, f32::max)
+            * self.scale
+    }
+
+    /// Lleva un punto del mundo a los ejes de la nave (los del colisionador)
+    pub fn world_to_local(&self, point: DVec3) -> Vec3 {
+        self.orientation.conjugate().rotate((point - self.position).to_vec3())
+    }
+
+    /// Rayo contra la malla de la nave; devuelve el impacto en ejes de la nave
+    pub fn ray_cast(&self, origin: DVec3, direction: Vec3, max_t: f32) -> Option<RayHit> {
+        let local = Ray::new(self.world_to_local(origin), self.orientation.conjugate().rotate(direction));
+        self.collider.ray_cast(&local, max_t)
+    }
+
+    /// Matriz de modelo relativa a `origin` (la cámara)
+    pub fn model_matrix(&self, origin: DVec3) -> Mat4 {
+        let pos = (self.position - origin).to_vec3();
+        Mat4::translation(pos.x, pos.y, pos.z) * self.orientation.to_mat4() * mesh_matrix(self.scale)
+    }
+}
+
+/// De la malla a los ejes de la nave.
+/// El OBJ mira hacia +X, se gira -90° en Y para alinearlo con +Z
+fn mesh_matrix(scale: f32) -> Mat4 {
+    Mat4::rotation_y(-std::f32::consts::FRAC_PI_2) * Mat4::scale(scale, scale, scale)
+}
+
+/// Asiento del piloto: arriba y algo adelantado dentro de la caja de la malla
+fn seat_in(collider: &Bvh) -> Vec3 {
+    if collider.is_empty() {
+        return Vec3::zero();
+    }
+    let b = collider.bounds();
+    let size = b.max - b.min;
+    Vec3::new(b.center().x, b.min.y + size.y * 0.8, b.center().z + size.z * 0.2)
+}
+
+/// Nave con una esfera unitaria como malla (radio 0.4 con la escala por
+/// defecto): la base de los tests de vuelo, cámaras y colisiones
+#[cfg(test)]
+pub(crate) fn test_ship() -> SpaceShip {
+    SpaceShip::new(super::generate_sphere(8, 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    fn step(ship: &mut SpaceShip, seconds: f32) {
+        let dt = 1.0 / 60.0;
+        for _ in 0..(seconds / dt) as usize {
+            ship.update(dt, DVec3::zero());
+        }
+    }
+
+    #[test]
+    fn test_thrust_accelerates_along_forward() {
+        let mut s = test_ship();
+        s.flight_assist = false;
+        s.set_thrust(Vec3::new(0.0, 0.0, 1.0));
+        step(&mut s, 1.0);
+
+        // a = F / m = 20 → v ≈ 20 tras 1 s
+        assert!((s.velocity.z - 20.0).abs() < 0.5);
+        assert!(s.velocity.x.abs() < 1e-6 && s.velocity.y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_inertia_without_flight_assist() {
+        let mut s = test_ship();
+        s.flight_assist = false;
+        s.set_thrust(Vec3::new(0.0, 0.0, 1.0));
+        step(&mut s, 0.5);
+        let v = s.velocity;
+
+        s.set_thrust(Vec3::zero());
+        step(&mut s, 2.0);
+        assert_eq!(s.velocity, v);
+    }
+
+    #[test]
+    fn test_flight_assist_dampens_drift() {
+        let mut s = test_ship();
+        s.velocity = DVec3::new(5.0, -3.0, 8.0);
+        s.angular_velocity = Vec3::new(0.0, 2.0, 0.0);
+        step(&mut s, 3.0);
+
+        assert!(s.velocity.length() < 1e-3);
+        assert!(s.angular_velocity.length() < 1e-3);
+    }
+
+    #[test]
+    fn test_heavier_ship_accelerates_less() {
+        let mut light = test_ship();
+        let mut heavy = test_ship();
+        heavy.mass = 4.0;
+        for s in [&mut light, &mut heavy] {
+            s.flight_assist = false;
+            s.set_thrust(Vec3::new(0.0, 0.0, 1.0));
+            step(s, 1.0);
         }
+        assert!((light.velocity.z / heavy.velocity.z - 4.0).abs() < 0.1);
     }
 
-    pub fn update_from_camera(&mut self, camera: &crate::camera::FreeCamera) {
-        // La nave rota EXACTAMENTE como la cámara
-        self.yaw = camera.yaw;
-        self.pitch = camera.pitch;
+    #[test]
+    fn test_gravity_bends_trajectory_into_orbit() {
+        // Órbita circular de radio 6 alrededor de un cuerpo con μ = 20 en el origen
+        let mu = 20.0;
+        let r = 6.0;
+        let mut s = test_ship();
+        s.flight_assist = false;
+        s.position = DVec3::new(r, 0.0, 0.0);
+        s.velocity = DVec3::new(0.0, 0.0, (mu / r).sqrt());
+
+        let dt = 1.0 / 120.0;
+        let period = 2.0 * std::f64::consts::PI * (r * r * r / mu).sqrt();
+        for _ in 0..(period / dt as f64) as usize {
+            let d = s.position.length();
+            let g = s.position * (-mu / (d * d * d));
+            s.update(dt, g);
+            assert!((s.position.length() - r).abs() < 0.05);
+        }
 
-        // La nave va un poco enfrente de la cámara
-        self.position = camera.position + camera.forward() * 2.5;
+        // Tras un periodo vuelve cerca del punto de partida
+        assert!(s.position.distance(&DVec3::new(r, 0.0, 0.0)) < 0.2);
     }
 
-    pub fn model_matrix(&self) -> Mat4 {
-        Mat4::translation(self.position.x, self.position.y, self.position.z)
-            * Mat4::rotation_y(self.yaw)
-            * Mat4::rotation_x(self.pitch)
-            * Mat4::scale(self.scale, self.scale, self.scale)
+    #[test]
+    fn test_torque_rotates_and_keeps_spinning() {
+        let mut s = test_ship();
+        s.flight_assist = false;
+        s.set_torque(Vec3::new(0.0, 1.0, 0.0));
+        step(&mut s, 0.5);
+        s.set_torque(Vec3::zero());
+        let omega = s.angular_velocity;
+        step(&mut s, 0.5);
+
+        assert!((s.angular_velocity - omega).length() < 1e-5);
+        // Yaw positivo gira +Z hacia +X
+        assert!(s.forward().x > 0.1);
     }
 }
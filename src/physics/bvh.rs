@@ -0,0 +1,779 @@
+use crate::math::{Mat4, Vec3};
+use crate::models::Mesh;
+
+/// Máximo de triángulos por hoja
+const LEAF_SIZE: usize = 4;
+const EPSILON: f32 = 1e-7;
+
+fn axis(v: Vec3, i: usize) -> f32 {
+    match i {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
+
+fn min_v(a: Vec3, b: Vec3) -> Vec3 {
+    Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z))
+}
+
+fn max_v(a: Vec3, b: Vec3) -> Vec3 {
+    Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+}
+
+// =========================================
+// Rayo
+// =========================================
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3, // normalizada
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self { origin, direction: direction.normalize() }
+    }
+
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+}
+
+/// Resultado de un ray cast o sphere cast contra una malla
+#[derive(Debug, Clone, Copy)]
+pub struct RayHit {
+    pub t: f32,       // distancia recorrida a lo largo de la dirección
+    pub point: Vec3,  // punto de contacto sobre la malla
+}
+
+// =========================================
+// Caja alineada a los ejes
+// =========================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn empty() -> Self {
+        Self {
+            min: Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: Vec3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    pub fn grow(&mut self, p: Vec3) {
+        self.min = min_v(self.min, p);
+        self.max = max_v(self.max, p);
+    }
+
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self { min: min_v(self.min, other.min), max: max_v(self.max, other.max) }
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x
+            && self.min.y <= other.max.y && self.max.y >= other.min.y
+            && self.min.z <= other.max.z && self.max.z >= other.min.z
+    }
+
+    /// Caja agrandada `r` en todas las direcciones
+    pub fn expanded(&self, r: f32) -> Self {
+        let d = Vec3::new(r, r, r);
+        Self { min: self.min - d, max: self.max + d }
+    }
+
+    /// Caja que envuelve esta caja transformada por `m`
+    pub fn transformed(&self, m: &Mat4) -> Self {
+        let mut out = Aabb::empty();
+        for i in 0..8 {
+            let corner = Vec3::new(
+                if i & 1 == 0 { self.min.x } else { self.max.x },
+                if i & 2 == 0 { self.min.y } else { self.max.y },
+                if i & 4 == 0 { self.min.z } else { self.max.z },
+            );
+            out.grow(m.mul_point(corner));
+        }
+        out
+    }
+
+    /// Distancia de entrada del rayo a la caja (método de slabs), si es < `max_t`
+    pub fn ray_entry(&self, ray: &Ray, max_t: f32) -> Option<f32> {
+        let mut t_min = 0.0f32;
+        let mut t_max = max_t;
+
+        for i in 0..3 {
+            let o = axis(ray.origin, i);
+            let d = axis(ray.direction, i);
+            let (lo, hi) = (axis(self.min, i), axis(self.max, i));
+
+            if d.abs() < EPSILON {
+                if o < lo || o > hi {
+                    return None;
+                }
+                continue;
+            }
+
+            let inv = 1.0 / d;
+            let (mut t0, mut t1) = ((lo - o) * inv, (hi - o) * inv);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_min > t_max {
+                return None;
+            }
+        }
+
+        Some(t_min)
+    }
+}
+
+// =========================================
+// Triángulo
+// =========================================
+
+#[derive(Debug, Clone, Copy)]
+pub struct Triangle {
+    pub a: Vec3,
+    pub b: Vec3,
+    pub c: Vec3,
+}
+
+impl Triangle {
+    pub fn new(a: Vec3, b: Vec3, c: Vec3) -> Self {
+        Self { a, b, c }
+    }
+
+    pub fn normal(&self) -> Vec3 {
+        (self.b - self.a).cross(&(self.c - self.a)).normalize()
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        let mut b = Aabb::empty();
+        b.grow(self.a);
+        b.grow(self.b);
+        b.grow(self.c);
+        b
+    }
+
+    pub fn centroid(&self) -> Vec3 {
+        (self.a + self.b + self.c) / 3.0
+    }
+
+    pub fn transformed(&self, m: &Mat4) -> Self {
+        Self::new(m.mul_point(self.a), m.mul_point(self.b), m.mul_point(self.c))
+    }
+
+    /// Intersección rayo-triángulo por ambas caras (Möller–Trumbore)
+    pub fn ray_intersect(&self, ray: &Ray) -> Option<f32> {
+        let e1 = self.b - self.a;
+        let e2 = self.c - self.a;
+        let p = ray.direction.cross(&e2);
+        let det = e1.dot(&p);
+        if det.abs() < EPSILON {
+            return None;
+        }
+
+        let inv = 1.0 / det;
+        let s = ray.origin - self.a;
+        let u = s.dot(&p) * inv;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = s.cross(&e1);
+        let v = ray.direction.dot(&q) * inv;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = e2.dot(&q) * inv;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// Punto del triángulo más cercano a `p` (Ericson, Real-Time Collision Detection 5.1.5)
+    pub fn closest_point(&self, p: Vec3) -> Vec3 {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let ab = b - a;
+        let ac = c - a;
+
+        let ap = p - a;
+        let d1 = ab.dot(&ap);
+        let d2 = ac.dot(&ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return a;
+        }
+
+        let bp = p - b;
+        let d3 = ab.dot(&bp);
+        let d4 = ac.dot(&bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return b;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        let cp = p - c;
+        let d5 = ab.dot(&cp);
+        let d6 = ac.dot(&cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return c;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        let denom = 1.0 / (va + vb + vc);
+        a + ab * (vb * denom) + ac * (vc * denom)
+    }
+
+    /// Barre una esfera de radio `radius` desde `ray.origin` a lo largo de
+    /// `ray.direction` hasta `max_t`. Devuelve la distancia y el punto de contacto.
+    /// Si ya se solapan, solo cuenta como impacto cuando la esfera se acerca
+    pub fn sphere_cast(&self, ray: &Ray, radius: f32, max_t: f32) -> Option<(f32, Vec3)> {
+        let closest = self.closest_point(ray.origin);
+        let offset = ray.origin - closest;
+        if offset.length_squared() <= radius * radius {
+            return (ray.direction.dot(&offset) < 0.0).then_some((0.0, closest));
+        }
+
+        // 1) Contra la cara: el primer contacto posible si cae dentro del triángulo.
+        //    Los triángulos degenerados no tienen cara útil, solo aristas
+        let n = self.normal();
+        let dist = n.dot(&(ray.origin - self.a));
+        let side = if dist >= 0.0 { n } else { -n };
+        let approach = -ray.direction.dot(&side);
+        if !self.is_degenerate() && approach > EPSILON {
+            let t = (dist.abs() - radius) / approach;
+            if (0.0..=max_t).contains(&t) {
+                let contact = ray.at(t) - side * radius;
+                if self.contains_coplanar(contact) {
+                    return Some((t, contact));
+                }
+            }
+        }
+
+        // 2) Contra aristas (cilindros) y vértices (esferas)
+        let mut best: Option<(f32, Vec3)> = None;
+        let mut consider = |hit: Option<(f32, Vec3)>| {
+            if let Some((t, p)) = hit
+                && t <= max_t
+                && best.is_none_or(|(bt, _)| t < bt)
+            {
+                best = Some((t, p));
+            }
+        };
+
+        for (p, q) in [(self.a, self.b), (self.b, self.c), (self.c, self.a)] {
+            consider(ray_vs_segment_capsule(ray, p, q, radius));
+            consider(ray_vs_sphere(ray, p, radius).map(|t| (t, p)));
+        }
+
+        best
+    }
+
+    fn is_degenerate(&self) -> bool {
+        (self.b - self.a).cross(&(self.c - self.a)).length_squared() < EPSILON * EPSILON
+    }
+
+    /// ¿El punto (ya en el plano del triángulo) está dentro?
+    fn contains_coplanar(&self, p: Vec3) -> bool {
+        let n = self.normal();
+        let edges = [(self.a, self.b), (self.b, self.c), (self.c, self.a)];
+        edges
+            .iter()
+            .all(|(u, v)| (*v - *u).cross(&(p - *u)).dot(&n) >= -EPSILON)
+    }
+
+    /// Solapamiento triángulo-triángulo por ejes separadores
+    pub fn intersects(&self, other: &Triangle) -> bool {
+        let ea = [self.b - self.a, self.c - self.b, self.a - self.c];
+        let eb = [other.b - other.a, other.c - other.b, other.a - other.c];
+        let na = ea[0].cross(&ea[1]);
+        let nb = eb[0].cross(&eb[1]);
+
+        let mut axes = vec![na, nb];
+        for a in &ea {
+            for b in &eb {
+                axes.push(a.cross(b));
+            }
+        }
+        // Para el caso coplanar: normales de las aristas dentro del plano
+        for e in &ea {
+            axes.push(na.cross(e));
+        }
+        for e in &eb {
+            axes.push(nb.cross(e));
+        }
+
+        let project = |t: &Triangle, axis: &Vec3| {
+            let (p0, p1, p2) = (axis.dot(&t.a), axis.dot(&t.b), axis.dot(&t.c));
+            (p0.min(p1).min(p2), p0.max(p1).max(p2))
+        };
+
+        axes.iter()
+            .filter(|axis| axis.length_squared() > EPSILON * EPSILON)
+            .all(|axis| {
+                let (min_a, max_a) = project(self, axis);
+                let (min_b, max_b) = project(other, axis);
+                min_a <= max_b && min_b <= max_a
+            })
+    }
+}
+
+/// Rayo contra esfera; `t >= 0` del primer contacto
+fn ray_vs_sphere(ray: &Ray, center: Vec3, radius: f32) -> Option<f32> {
+    let m = ray.origin - center;
+    let b = m.dot(&ray.direction);
+    let c = m.length_squared() - radius * radius;
+    if c > 0.0 && b > 0.0 {
+        return None;
+    }
+    let disc = b * b - c;
+    if disc < 0.0 {
+        return None;
+    }
+    Some((-b - disc.sqrt()).max(0.0))
+}
+
+/// Rayo contra el cilindro de radio `radius` alrededor del segmento `p`-`q`
+/// (las tapas las cubren las esferas de los vértices)
+fn ray_vs_segment_capsule(ray: &Ray, p: Vec3, q: Vec3, radius: f32) -> Option<(f32, Vec3)> {
+    let e = q - p;
+    let ee = e.dot(&e);
+    if ee < EPSILON {
+        return None;
+    }
+
+    let m = ray.origin - p;
+    let d_perp = ray.direction - e * (ray.direction.dot(&e) / ee);
+    let m_perp = m - e * (m.dot(&e) / ee);
+
+    let a = d_perp.length_squared();
+    if a < EPSILON {
+        return None; // paralelo a la arista
+    }
+    let b = 2.0 * m_perp.dot(&d_perp);
+    let c = m_perp.length_squared() - radius * radius;
+    let disc = b * b - 4.0 * a * c;
+    if disc < 0.0 {
+        return None;
+    }
+
+    let t = (-b - disc.sqrt()) / (2.0 * a);
+    if t < 0.0 {
+        return None;
+    }
+
+    let s = (m + ray.direction * t).dot(&e) / ee;
+    (0.0..=1.0).contains(&s).then(|| (t, p + e * s))
+}
+
+// =========================================
+// Jerarquía de volúmenes envolventes
+// =========================================
+
+#[derive(Debug, Clone, Copy)]
+enum BvhNode {
+    Leaf { bounds: Aabb, start: usize, count: usize },
+    Branch { bounds: Aabb, left: usize, right: usize },
+}
+
+impl BvhNode {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { bounds, .. } | BvhNode::Branch { bounds, .. } => bounds,
+        }
+    }
+}
+
+/// BVH de triángulos construida a partir de una `Mesh`.
+/// Los triángulos se reordenan para que cada hoja sea un rango contiguo
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    triangles: Vec<Triangle>,
+}
+
+impl Bvh {
+    pub fn build(mesh: &Mesh) -> Self {
+        Self::build_transformed(mesh, &Mat4::identity())
+    }
+
+    /// Construye la BVH sobre los vértices transformados por `transform`
+    pub fn build_transformed(mesh: &Mesh, transform: &Mat4) -> Self {
+        let mut triangles: Vec<Triangle> = mesh
+            .faces
+            .iter()
+            .filter(|(a, b, c)| {
+                *a < mesh.vertices.len() && *b < mesh.vertices.len() && *c < mesh.vertices.len()
+            })
+            .map(|&(a, b, c)| Triangle::new(mesh.vertices[a], mesh.vertices[b], mesh.vertices[c]).transformed(transform))
+            .collect();
+
+        let mut nodes = Vec::new();
+        if !triangles.is_empty() {
+            let len = triangles.len();
+            Self::build_node(&mut nodes, &mut triangles, 0, len);
+        }
+
+        Self { nodes, triangles }
+    }
+
+    /// Divide por la mediana de los centroides sobre el eje más largo
+    fn build_node(nodes: &mut Vec<BvhNode>, items: &mut [Triangle], start: usize, end: usize) -> usize {
+        let slice = &mut items[start..end];
+        let bounds = slice.iter().fold(Aabb::empty(), |b, t| b.union(&t.bounds()));
+        let index = nodes.len();
+
+        if slice.len() <= LEAF_SIZE {
+            nodes.push(BvhNode::Leaf { bounds, start, count: slice.len() });
+            return index;
+        }
+
+        let mut centroids = Aabb::empty();
+        for t in slice.iter() {
+            centroids.grow(t.centroid());
+        }
+        let extent = centroids.max - centroids.min;
+        let split_axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+
+        let mid = slice.len() / 2;
+        slice.select_nth_unstable_by(mid, |a, b| {
+            axis(a.centroid(), split_axis).total_cmp(&axis(b.centroid(), split_axis))
+        });
+
+        // Se reserva el nodo y se rellena cuando se conocen los hijos
+        nodes.push(BvhNode::Leaf { bounds, start, count: 0 });
+        let left = Self::build_node(nodes, items, start, start + mid);
+        let right = Self::build_node(nodes, items, start + mid, end);
+        nodes[index] = BvhNode::Branch { bounds, left, right };
+        index
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        self.nodes.first().map(|n| *n.bounds()).unwrap_or_else(Aabb::empty)
+    }
+
+    /// Primer triángulo que toca el rayo antes de `max_t`
+    pub fn ray_cast(&self, ray: &Ray, max_t: f32) -> Option<RayHit> {
+        let mut best: Option<RayHit> = None;
+        let mut limit = max_t;
+
+        self.traverse(
+            |bounds| bounds.ray_entry(ray, max_t),
+            |i| {
+                if let Some(t) = self.triangles[i].ray_intersect(ray)
+                    && t < limit
+                {
+                    limit = t;
+                    best = Some(RayHit { t, point: ray.at(t) });
+                }
+                limit
+            },
+        );
+        best
+    }
+
+    /// Primer contacto de una esfera barrida a lo largo del rayo antes de `max_t`
+    pub fn sphere_cast(&self, ray: &Ray, radius: f32, max_t: f32) -> Option<RayHit> {
+        let mut best: Option<RayHit> = None;
+        let mut limit = max_t;
+
+        self.traverse(
+            |bounds| bounds.expanded(radius).ray_entry(ray, max_t),
+            |i| {
+                if let Some((t, point)) = self.triangles[i].sphere_cast(ray, radius, limit)
+                    && best.is_none_or(|b| t < b.t)
+                {
+                    limit = t;
+                    best = Some(RayHit { t, point });
+                }
+                limit
+            },
+        );
+        best
+    }
+
+    /// ¿Se tocan las dos mallas? `other_to_self` lleva el espacio de `other` al de esta BVH
+    pub fn overlaps(&self, other: &Bvh, other_to_self: &Mat4) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return false;
+        }
+
+        let mut stack = vec![(0usize, 0usize)];
+        while let Some((a, b)) = stack.pop() {
+            let node_a = &self.nodes[a];
+            let node_b = &other.nodes[b];
+            if !node_a.bounds().intersects(&node_b.bounds().transformed(other_to_self)) {
+                continue;
+            }
+
+            match (node_a, node_b) {
+                (
+                    BvhNode::Leaf { start: sa, count: ca, .. },
+                    BvhNode::Leaf { start: sb, count: cb, .. },
+                ) => {
+                    for tb in &other.triangles[*sb..sb + cb] {
+                        let tb = tb.transformed(other_to_self);
+                        if self.triangles[*sa..sa + ca].iter().any(|ta| ta.intersects(&tb)) {
+                            return true;
+                        }
+                    }
+                }
+                (BvhNode::Branch { left, right, .. }, BvhNode::Leaf { .. }) => {
+                    stack.push((*left, b));
+                    stack.push((*right, b));
+                }
+                (_, BvhNode::Branch { left, right, .. }) => {
+                    stack.push((a, *left));
+                    stack.push((a, *right));
+                }
+            }
+        }
+        false
+    }
+
+    /// Recorre el árbol en orden de cercanía. `enter` da la distancia de entrada a
+    /// un nodo (None = se poda); `visit` prueba un triángulo y devuelve el nuevo límite
+    fn traverse(
+        &self,
+        enter: impl Fn(&Aabb) -> Option<f32>,
+        mut visit: impl FnMut(usize) -> f32,
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let mut limit = f32::INFINITY;
+        let mut stack = vec![(0usize, 0.0f32)];
+        while let Some((index, entry)) = stack.pop() {
+            if entry > limit {
+                continue;
+            }
+            match self.nodes[index] {
+                BvhNode::Leaf { start, count, .. } => {
+                    for i in start..start + count {
+                        limit = limit.min(visit(i));
+                    }
+                }
+                BvhNode::Branch { left, right, .. } => {
+                    let l = enter(self.nodes[left].bounds()).map(|t| (left, t));
+                    let r = enter(self.nodes[right].bounds()).map(|t| (right, t));
+                    // El más cercano se apila al final para visitarlo primero
+                    match (l, r) {
+                        (Some(l), Some(r)) if l.1 <= r.1 => stack.extend([r, l]),
+                        (Some(l), Some(r)) => stack.extend([l, r]),
+                        (Some(n), None) | (None, Some(n)) => stack.push(n),
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{generate_sphere, load_obj};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::time::Instant;
+
+    /// Referencia: todos los triángulos contra el rayo
+    fn ray_cast_brute(mesh: &Mesh, ray: &Ray) -> Option<(usize, f32)> {
+        mesh.faces
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &(a, b, c))| {
+                let tri = Triangle::new(mesh.vertices[a], mesh.vertices[b], mesh.vertices[c]);
+                tri.ray_intersect(ray).map(|t| (i, t))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    fn sphere_cast_brute(mesh: &Mesh, ray: &Ray, radius: f32, max_t: f32) -> Option<f32> {
+        mesh.faces
+            .iter()
+            .filter_map(|&(a, b, c)| {
+                let tri = Triangle::new(mesh.vertices[a], mesh.vertices[b], mesh.vertices[c]);
+                tri.sphere_cast(ray, radius, max_t).map(|(t, _)| t)
+            })
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    fn random_ray(rng: &mut StdRng) -> Ray {
+        let mut v = || Vec3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+        let origin = v().normalize() * 3.0;
+        let target = v() * 0.5;
+        Ray::new(origin, target - origin)
+    }
+
+    #[test]
+    fn test_ray_hits_sphere_mesh() {
+        let mesh = generate_sphere(16, 16);
+        let bvh = Bvh::build(&mesh);
+
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::unit_z());
+        let hit = bvh.ray_cast(&ray, f32::INFINITY).unwrap();
+        assert!((hit.t - 4.0).abs() < 0.05);
+        assert!(hit.point.z < 0.0);
+
+        let miss = Ray::new(Vec3::new(0.0, 2.0, -5.0), Vec3::unit_z());
+        assert!(bvh.ray_cast(&miss, f32::INFINITY).is_none());
+        assert!(bvh.ray_cast(&ray, 3.0).is_none());
+    }
+
+    #[test]
+    fn test_ray_cast_matches_brute_force() {
+        let mesh = generate_sphere(12, 12);
+        let bvh = Bvh::build(&mesh);
+        let mut rng = StdRng::seed_from_u64(35);
+
+        for _ in 0..200 {
+            let ray = random_ray(&mut rng);
+            let fast = bvh.ray_cast(&ray, f32::INFINITY).map(|h| h.t);
+            let slow = ray_cast_brute(&mesh, &ray).map(|(_, t)| t);
+            match (fast, slow) {
+                (Some(a), Some(b)) => assert!((a - b).abs() < 1e-4, "{a} vs {b}"),
+                (a, b) => assert_eq!(a.is_some(), b.is_some()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_sphere_cast_matches_brute_force() {
+        let mesh = generate_sphere(8, 8);
+        let bvh = Bvh::build(&mesh);
+        let mut rng = StdRng::seed_from_u64(350);
+
+        for _ in 0..200 {
+            let ray = random_ray(&mut rng);
+            let fast = bvh.sphere_cast(&ray, 0.3, 10.0).map(|h| h.t);
+            let slow = sphere_cast_brute(&mesh, &ray, 0.3, 10.0);
+            match (fast, slow) {
+                (Some(a), Some(b)) => assert!((a - b).abs() < 1e-4, "{a} vs {b}"),
+                (a, b) => assert_eq!(a.is_some(), b.is_some()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_sphere_cast_contact_types() {
+        let tri = Triangle::new(
+            Vec3::new(-1.0, 0.0, -1.0),
+            Vec3::new(1.0, 0.0, -1.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        );
+
+        // Cara: cae de frente
+        let down = Ray::new(Vec3::new(0.0, 5.0, 0.0), -Vec3::unit_y());
+        let (t, p) = tri.sphere_cast(&down, 1.0, 10.0).unwrap();
+        assert!((t - 4.0).abs() < 1e-5);
+        assert!(p.length() < 1e-5);
+
+        // Vértice: llega de frente a la punta, por encima del plano
+        let past_tip = Ray::new(Vec3::new(0.0, 0.5, 6.0), -Vec3::unit_z());
+        let (t, p) = tri.sphere_cast(&past_tip, 1.0, 10.0).unwrap();
+        assert!((p - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-5);
+        assert!((t - (5.0 - 0.75f32.sqrt())).abs() < 1e-4);
+
+        // Alejándose desde un solapamiento no es impacto
+        let leaving = Ray::new(Vec3::new(0.0, 0.5, 0.0), Vec3::unit_y());
+        assert!(tri.sphere_cast(&leaving, 1.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn test_mesh_overlap() {
+        let mesh = generate_sphere(8, 8);
+        let bvh = Bvh::build(&mesh);
+
+        assert!(bvh.overlaps(&bvh, &Mat4::translation(1.5, 0.0, 0.0)));
+        assert!(!bvh.overlaps(&bvh, &Mat4::translation(2.5, 0.0, 0.0)));
+        // Una esfera pequeña dentro de la grande no toca su superficie
+        assert!(!bvh.overlaps(&bvh, &Mat4::scale(0.3, 0.3, 0.3)));
+    }
+
+    #[test]
+    fn test_triangle_overlap() {
+        let a = Triangle::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 2.0, 0.0));
+        let crossing = Triangle::new(Vec3::new(0.5, 0.5, -1.0), Vec3::new(0.5, 0.5, 1.0), Vec3::new(3.0, 3.0, 0.0));
+        let apart = Triangle::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(2.0, 0.0, 1.0), Vec3::new(0.0, 2.0, 1.0));
+        assert!(a.intersects(&crossing));
+        assert!(!a.intersects(&apart));
+    }
+
+    /// Comparativa fuerza bruta vs BVH con la nave. Los tiempos solo se informan:
+    /// `cargo test --release bench_ship -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_ship_ray_and_sphere_casts() {
+        let mesh = load_obj("assets/models/ship.obj", 1.0);
+        let build = Instant::now();
+        let bvh = Bvh::build(&mesh);
+        println!("BVH de {} triángulos en {:?}", mesh.faces.len(), build.elapsed());
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let radius = (bvh.bounds().max - bvh.bounds().min).length() * 0.5;
+        let rays: Vec<Ray> = (0..2_000)
+            .map(|_| {
+                let r = random_ray(&mut rng);
+                Ray::new(bvh.bounds().center() + r.origin * radius, r.direction)
+            })
+            .collect();
+
+        let start = Instant::now();
+        let brute = rays.iter().filter(|r| ray_cast_brute(&mesh, r).is_some()).count();
+        let brute_time = start.elapsed();
+
+        let start = Instant::now();
+        let fast = rays.iter().filter(|r| bvh.ray_cast(r, f32::INFINITY).is_some()).count();
+        let bvh_time = start.elapsed();
+
+        println!("ray cast    fuerza bruta: {:?}  BVH: {:?}", brute_time, bvh_time);
+        assert_eq!(brute, fast);
+
+        let cast_radius = radius * 0.05;
+        let start = Instant::now();
+        let brute = rays.iter().filter(|r| sphere_cast_brute(&mesh, r, cast_radius, f32::INFINITY).is_some()).count();
+        let brute_time = start.elapsed();
+
+        let start = Instant::now();
+        let fast = rays.iter().filter(|r| bvh.sphere_cast(r, cast_radius, f32::INFINITY).is_some()).count();
+        let bvh_time = start.elapsed();
+
+        println!("sphere cast fuerza bruta: {:?}  BVH: {:?}", brute_time, bvh_time);
+        assert_eq!(brute, fast);
+    }
+}
@@ -1,9 +1,11 @@
-use crate::math::Vec3;
+use crate::math::{DVec3, Mat4};
+use super::bvh::Ray;
+use crate::models::SpaceShip;
 use crate::scene::solar_system::SolarSystem;
 use crate::scene::celestial_body::CelestialBody;
 
 /// Corrige la posición de la cámara si está demasiado cerca de algún cuerpo
-pub fn resolve_camera_collisions(system: &SolarSystem, camera_pos: &mut Vec3, margin_factor: f32) {
+pub fn resolve_camera_collisions(system: &SolarSystem, camera_pos: &mut DVec3, margin_factor: f32) {
     handle_body(&system.sun, camera_pos, margin_factor);
 
     for planet in &system.planets {
@@ -11,9 +13,9 @@ pub fn resolve_camera_collisions(system: &SolarSystem, camera_pos: &mut Vec3, ma
     }
 }
 
-fn handle_body(body: &CelestialBody, camera_pos: &mut Vec3, margin_factor: f32) {
+fn handle_body(body: &CelestialBody, camera_pos: &mut DVec3, margin_factor: f32) {
     let center = body.position();
-    let min_dist = body.radius * margin_factor;
+    let min_dist = (body.radius * margin_factor) as f64;
 
     let offset = *camera_pos - center;
     let dist = offset.length();
@@ -23,3 +25,451 @@ fn handle_body(body: &CelestialBody, camera_pos: &mut Vec3, margin_factor: f32)
         *camera_pos = center + dir * min_dist;
     }
 }
+
+// =========================================
+// Colisión continua (swept sphere) de la nave
+// =========================================
+
+/// Qué hacer cuando la nave toca un cuerpo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CollisionResponse {
+    /// Rebota conservando `restitution` de la velocidad normal (0 = sin rebote)
+    Bounce { restitution: f64 },
+    /// Pierde la velocidad normal y desliza sobre la superficie
+    #[allow(dead_code)]
+    Slide,
+    /// Queda destruida y pegada a la superficie
+    Crash,
+}
+
+/// Configuración de colisiones de la nave
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionSettings {
+    pub response: CollisionResponse,
+    /// Por encima de esta velocidad de impacto la nave se estrella siempre
+    pub crash_speed: Option<f64>,
+}
+
+/// Lugar donde quedó la nave estrellada: se mueve con el cuerpo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrashSite {
+    pub body: usize,
+    pub offset: DVec3,
+}
+
+/// Información de un impacto
+#[derive(Debug, Clone)]
+pub struct CollisionEvent {
+    pub body: usize,          // índice como en `SolarSystem::body`
+    pub body_name: String,
+    pub impact_speed: f64,    // velocidad normal relativa al cuerpo
+    pub crashed: bool,
+}
+
+/// Separación que se deja tras un contacto para no arrancar el siguiente paso solapados
+pub const CONTACT_SKIN: f64 = 1e-3;
+
+/// Tiempo de impacto (fracción [0, 1] del paso) entre una esfera de radio `r`
+/// que va de `p0` a `p1` y otra de radio `body_r` que va de `c0` a `c1`.
+/// Ambos movimientos se asumen lineales dentro del paso, así que no hay túnel
+/// aunque la nave atraviese el cuerpo entero en un solo frame
+pub fn sweep_sphere_sphere(
+    p0: DVec3,
+    p1: DVec3,
+    r: f64,
+    c0: DVec3,
+    c1: DVec3,
+    body_r: f64,
+) -> Option<f64> {
+    let radius = r + body_r;
+    let d0 = p0 - c0;
+    let motion = (p1 - c1) - d0;
+
+    let a = motion.length_squared();
+    let b = 2.0 * d0.dot(&motion);
+    let c = d0.length_squared() - radius * radius;
+    if c <= 0.0 {
+        // Ya se solapan al inicio del paso: solo es contacto si se siguen acercando
+        return (a >= 1e-18 && b < 0.0).then_some(0.0);
+    }
+
+    if a < 1e-18 || b >= 0.0 {
+        // Sin movimiento relativo o alejándose
+        return None;
+    }
+
+    let disc = b * b - 4.0 * a * c;
+    if disc < 0.0 {
+        return None;
+    }
+
+    let t = (-b - disc.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+/// Barre la nave desde `previous_position` hasta su posición actual contra todos
+/// los cuerpos (que ya avanzaron `dt`) y aplica la respuesta configurada al
+/// primer impacto. Una nave estrellada solo acompaña a su cuerpo
+pub fn resolve_ship_collisions(
+    ship: &mut SpaceShip,
+    previous_position: DVec3,
+    system: &SolarSystem,
+    dt: f32,
+    settings: CollisionSettings,
+) -> Option<CollisionEvent> {
+    if let Some(site) = ship.crashed {
+        if let Some(body) = system.body(site.body) {
+            ship.position = body.position() + site.offset;
+            ship.velocity = body.velocity();
+        }
+        return None;
+    }
+
+    let ship_radius = ship.bounding_radius() as f64;
+    let dt = dt as f64;
+
+    // Fase amplia: esfera envolvente de la nave; fase fina: su malla (BVH)
+    let (index, body, t, contact) = system
+        .bodies()
+        .enumerate()
+        .filter_map(|(i, body)| {
+            let (c0, c1) = (body.position_at(-dt), body.position());
+            let radius = body.radius as f64;
+            let t = sweep_sphere_sphere(previous_position, ship.position, ship_radius, c0, c1, radius)?;
+
+            let (t, contact) = if ship.collider.is_empty() {
+                let ship_at = previous_position.lerp(&ship.position, t);
+                let body_at = c0.lerp(&c1, t);
+                (t, body_at + (ship_at - body_at).normalize() * radius)
+            } else {
+                sweep_ship_mesh(ship, previous_position, c0, c1, radius)?
+            };
+            Some((i, body, t, contact))
+        })
+        .min_by(|a, b| a.2.total_cmp(&b.2))?;
+
+    // Geometría del contacto en el instante del impacto
+    let ship_at = previous_position.lerp(&ship.position, t);
+    let body_at = body.position_at(-dt).lerp(&body.position(), t);
+    let mut normal = (contact - body_at).normalize();
+    if normal.length_squared() == 0.0 {
+        normal = DVec3::new(0.0, 1.0, 0.0);
+    }
+
+    let rel_vel = ship.velocity - body.velocity();
+    let normal_speed = rel_vel.dot(&normal);
+    if normal_speed >= 0.0 {
+        // Se está separando (o quieta sobre la superficie): no hay impacto
+        return None;
+    }
+    let impact_speed = -normal_speed;
+
+    let response = match settings.crash_speed {
+        Some(limit) if impact_speed > limit => CollisionResponse::Crash,
+        _ => settings.response,
+    };
+
+    // Dejar la nave apoyada en la superficie, en la posición actual del cuerpo
+    let offset = ship_at - body_at + normal * CONTACT_SKIN;
+    ship.position = body.position() + offset;
+
+    let normal_part = normal * normal_speed.min(0.0);
+    match response {
+        CollisionResponse::Bounce { restitution } => {
+            ship.velocity = body.velocity() + rel_vel - normal_part * (1.0 + restitution);
+        }
+        CollisionResponse::Slide => {
+            ship.velocity = body.velocity() + rel_vel - normal_part;
+        }
+        CollisionResponse::Crash => {
+            ship.halt();
+            ship.velocity = body.velocity();
+            ship.crashed = Some(CrashSite { body: index, offset });
+        }
+    }
+
+    Some(CollisionEvent {
+        body: index,
+        body_name: body.name.clone(),
+        impact_speed,
+        crashed: response == CollisionResponse::Crash,
+    })
+}
+
+/// Distancia a la que se da por terminado un contacto
+pub const CONTACT_RELEASE: f64 = 0.1;
+
+/// Sigue con qué cuerpo está en contacto la nave, para avisar una sola vez por
+/// contacto aunque apoyada o deslizando choque en todos los frames
+#[derive(Debug, Default)]
+pub struct ContactTracker {
+    pub body: Option<usize>,
+}
+
+impl ContactTracker {
+    /// Actualiza con el impacto del frame (si hubo). Verdadero si es un contacto nuevo
+    pub fn update(&mut self, hit: Option<&CollisionEvent>, ship: &SpaceShip, system: &SolarSystem) -> bool {
+        if let Some(hit) = hit {
+            let new = self.body != Some(hit.body);
+            self.body = Some(hit.body);
+            return new;
+        }
+
+        // Sigue en contacto mientras no se aleje de la superficie
+        self.body = self.body.filter(|&i| system.body(i).is_some_and(|body| touching(ship, body, system)));
+        false
+    }
+}
+
+/// ¿La nave está a menos de `CONTACT_RELEASE` de la superficie del cuerpo? La
+/// esfera envolvente descarta rápido; con malla, la nave toca si su centro está
+/// dentro de la esfera del cuerpo agrandada en `CONTACT_RELEASE` o si su malla
+/// cruza esa esfera
+fn touching(ship: &SpaceShip, body: &CelestialBody, system: &SolarSystem) -> bool {
+    let offset = body.position() - ship.position;
+    let gap = offset.length() - ship.bounding_radius() as f64 - body.radius as f64;
+    if gap >= CONTACT_RELEASE {
+        return false;
+    }
+    let shell = body.radius + CONTACT_RELEASE as f32;
+    if ship.collider.is_empty() || offset.length() < shell as f64 {
+        return true;
+    }
+
+    let offset = offset.to_vec3();
+    let body_to_ship = ship.orientation.conjugate().to_mat4()
+        * Mat4::translation(offset.x, offset.y, offset.z)
+        * Mat4::scale(shell, shell, shell);
+    ship.collider.overlaps(&system.sphere_collider, &body_to_ship)
+}
+
+/// Barre el cuerpo (esfera de `c0` a `c1`) contra la malla de la nave, que va de
+/// `p0` a su posición actual. Se resuelve en ejes de la nave con su orientación
+/// actual; devuelve la fracción del paso y el punto de contacto en el mundo
+fn sweep_ship_mesh(ship: &SpaceShip, p0: DVec3, c0: DVec3, c1: DVec3, radius: f64) -> Option<(f64, DVec3)> {
+    let p1 = ship.position;
+    let to_local = ship.orientation.conjugate();
+    let start = to_local.rotate((c0 - p0).to_vec3());
+    let motion = to_local.rotate(((c1 - p1) - (c0 - p0)).to_vec3());
+
+    let length = motion.length();
+    if length < 1e-6 {
+        return None;
+    }
+
+    let ray = Ray::new(start, motion);
+    let hit = ship.collider.sphere_cast(&ray, radius as f32, length)?;
+    let t = (hit.t / length) as f64;
+    let ship_at = p0.lerp(&p1, t);
+    Some((t, ship_at + ship.orientation.rotate(hit.point)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{generate_sphere, test_ship, Mesh};
+    use crate::scene::solar_system::test_system;
+    use crate::math::Vec3;
+
+
+    #[test]
+    fn test_sweep_detects_tunneling() {
+        // Atraviesa una esfera de radio 1 entera en un solo paso
+        let t = sweep_sphere_sphere(
+            DVec3::new(-10.0, 0.0, 0.0),
+            DVec3::new(10.0, 0.0, 0.0),
+            0.5,
+            DVec3::zero(),
+            DVec3::zero(),
+            1.0,
+        )
+        .unwrap();
+        assert!((t - 8.5 / 20.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sweep_misses_and_moving_away() {
+        let miss = sweep_sphere_sphere(
+            DVec3::new(-10.0, 5.0, 0.0),
+            DVec3::new(10.0, 5.0, 0.0),
+            0.5,
+            DVec3::zero(),
+            DVec3::zero(),
+            1.0,
+        );
+        assert!(miss.is_none());
+
+        let away = sweep_sphere_sphere(
+            DVec3::new(3.0, 0.0, 0.0),
+            DVec3::new(6.0, 0.0, 0.0),
+            0.5,
+            DVec3::zero(),
+            DVec3::zero(),
+            1.0,
+        );
+        assert!(away.is_none());
+    }
+
+    #[test]
+    fn test_sweep_against_moving_body() {
+        // La nave está quieta y el cuerpo pasa por encima de ella
+        let t = sweep_sphere_sphere(
+            DVec3::zero(),
+            DVec3::zero(),
+            0.0,
+            DVec3::new(-4.0, 0.0, 0.0),
+            DVec3::new(4.0, 0.0, 0.0),
+            2.0,
+        )
+        .unwrap();
+        assert!((t - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sweep_overlapping_start() {
+        let sweep = |to: DVec3| sweep_sphere_sphere(DVec3::new(0.5, 0.0, 0.0), to, 0.1, DVec3::zero(), DVec3::zero(), 1.0);
+        // Solapadas y acercándose: contacto inmediato
+        assert_eq!(sweep(DVec3::new(0.4, 0.0, 0.0)), Some(0.0));
+        // Quietas o separándose no es un impacto
+        assert_eq!(sweep(DVec3::new(0.5, 0.0, 0.0)), None);
+        assert_eq!(sweep(DVec3::new(0.6, 0.0, 0.0)), None);
+    }
+
+    /// Nave que cae hacia el sol a `speed`; devuelve el evento y la nave
+    fn fall_into_sun(settings: CollisionSettings, speed: f64) -> (Option<CollisionEvent>, SpaceShip) {
+        let system = test_system();
+        let mut s = test_ship();
+        let previous = DVec3::new(0.0, 20.0, 0.0);
+        s.position = DVec3::new(0.0, -20.0, 0.0);
+        s.velocity = DVec3::new(1.0, -speed, 0.0);
+        let event = resolve_ship_collisions(&mut s, previous, &system, 1.0 / 60.0, settings);
+        (event, s)
+    }
+
+    #[test]
+    fn test_bounce_response() {
+        let settings = CollisionSettings {
+            response: CollisionResponse::Bounce { restitution: 0.5 },
+            crash_speed: None,
+        };
+        let (event, s) = fall_into_sun(settings, 10.0);
+        let event = event.unwrap();
+
+        assert_eq!(event.body, 0);
+        assert_eq!(event.body_name, "Sun");
+        assert!((event.impact_speed - 10.0).abs() < 1e-4);
+        assert!(!event.crashed);
+
+        // Quedó fuera del sol, por arriba, y sale rebotando a la mitad de velocidad
+        assert!((s.position.y - (4.4 + CONTACT_SKIN)).abs() < 1e-4);
+        assert!((s.velocity.y - 5.0).abs() < 1e-4);
+        assert!((s.velocity.x - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_slide_response() {
+        let settings = CollisionSettings { response: CollisionResponse::Slide, crash_speed: None };
+        let (event, s) = fall_into_sun(settings, 10.0);
+        assert!(event.is_some());
+        assert!(s.velocity.y.abs() < 1e-4);
+        assert!((s.velocity.x - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_mesh_narrow_phase_filters_bounding_hits() {
+        // Un triángulo diminuto por encima del centro: la esfera envolvente roza el
+        // sol al pasar, la malla no
+        let mesh = Mesh::new(
+            vec![
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec3::new(0.01, 1.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.01),
+            ],
+            vec![(0, 1, 2)],
+        );
+        let system = test_system();
+        let settings = CollisionSettings { response: CollisionResponse::Slide, crash_speed: None };
+
+        let mut s = SpaceShip::new(mesh.clone());
+        s.position = DVec3::new(20.0, 4.3, 0.0);
+        let hit = resolve_ship_collisions(&mut s, DVec3::new(-20.0, 4.3, 0.0), &system, 1.0 / 60.0, settings);
+        assert!(hit.is_none());
+
+        // Por debajo del sol el triángulo sí lo toca
+        let mut s = SpaceShip::new(mesh);
+        s.position = DVec3::new(20.0, -4.3, 0.0);
+        s.velocity = DVec3::new(40.0 * 60.0, 0.0, 0.0);
+        let hit = resolve_ship_collisions(&mut s, DVec3::new(-20.0, -4.3, 0.0), &system, 1.0 / 60.0, settings);
+        assert!(hit.is_some());
+    }
+
+    #[test]
+    fn test_crash_above_speed_limit() {
+        let settings = CollisionSettings {
+            response: CollisionResponse::Bounce { restitution: 0.5 },
+            crash_speed: Some(5.0),
+        };
+        let (event, mut s) = fall_into_sun(settings, 10.0);
+        assert!(event.unwrap().crashed);
+        assert!(s.crashed.is_some());
+
+        // Estrellada, acompaña al cuerpo y no genera más eventos
+        let system = test_system();
+        let before = s.position;
+        let again = resolve_ship_collisions(&mut s, before, &system, 1.0 / 60.0, settings);
+        assert!(again.is_none());
+        assert_eq!(s.position, before);
+    }
+
+    #[test]
+    fn test_resting_ship_reports_contact_once() {
+        let system = SolarSystem::new(generate_sphere(16, 16));
+        let settings = CollisionSettings { response: CollisionResponse::Slide, crash_speed: None };
+        let mut tracker = ContactTracker::default();
+        let (event, mut s) = fall_into_sun(settings, 10.0);
+        assert!(tracker.update(event.as_ref(), &s, &system));
+
+        // Apoyada sobre el sol, la gravedad la empuja contra la superficie cada frame
+        let dt = 1.0 / 60.0;
+        let mut new_contacts = 0;
+        for _ in 0..120 {
+            let previous = s.position;
+            s.velocity = s.velocity + DVec3::new(0.0, -5.0, 0.0) * dt as f64;
+            s.position = s.position + s.velocity * dt as f64;
+            let hit = resolve_ship_collisions(&mut s, previous, &system, dt, settings);
+            if let Some(hit) = &hit {
+                assert!(hit.impact_speed < 0.2);
+            }
+            new_contacts += tracker.update(hit.as_ref(), &s, &system) as usize;
+        }
+        assert_eq!(new_contacts, 0);
+        assert_eq!(tracker.body, Some(0));
+
+        // Al alejarse se suelta el contacto
+        s.position = DVec3::new(0.0, 10.0, 0.0);
+        tracker.update(None, &s, &system);
+        assert_eq!(tracker.body, None);
+    }
+
+    #[test]
+    fn test_contact_follows_the_mesh() {
+        // Nave chata (un triángulo horizontal): su esfera envolvente llega mucho
+        // más abajo que la malla
+        let system = SolarSystem::new(generate_sphere(16, 16));
+        let mesh = Mesh::new(
+            vec![Vec3::new(1.0, 0.0, 0.0), Vec3::new(-0.5, 0.0, 0.87), Vec3::new(-0.5, 0.0, -0.87)],
+            vec![(0, 1, 2)],
+        );
+        let mut s = SpaceShip::new(mesh);
+        let mut tracker = ContactTracker { body: Some(0) };
+
+        s.position = DVec3::new(0.0, 4.05, 0.0);
+        tracker.update(None, &s, &system);
+        assert_eq!(tracker.body, Some(0));
+
+        // Más arriba la esfera envolvente todavía tocaría, pero la malla no
+        s.position = DVec3::new(0.0, 4.25, 0.0);
+        tracker.update(None, &s, &system);
+        assert_eq!(tracker.body, None);
+    }
+}
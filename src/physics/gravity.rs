@@ -0,0 +1,102 @@
+use crate::math::DVec3;
+use crate::scene::solar_system::SolarSystem;
+use crate::scene::celestial_body::CelestialBody;
+
+/// Aceleración gravitatoria que ejerce un cuerpo, `dt` segundos en el futuro,
+/// sobre un punto. Dentro del cuerpo la distancia se limita al radio para evitar
+/// la singularidad
+pub fn gravity_from_body_at(body: &CelestialBody, point: DVec3, dt: f64) -> DVec3 {
+    let offset = body.position_at(dt) - point;
+    let dist = offset.length().max(body.radius as f64);
+    if dist <= 0.0 {
+        return DVec3::zero();
+    }
+    offset * (body.mu / (dist * dist * dist))
+}
+
+/// Suma N-body de la gravedad de todos los cuerpos del sistema
+pub fn gravity_at(system: &SolarSystem, point: DVec3) -> DVec3 {
+    gravity_at_time(system, point, 0.0)
+}
+
+/// Gravedad en `point` con los cuerpos donde estarán dentro de `dt` segundos
+pub fn gravity_at_time(system: &SolarSystem, point: DVec3, dt: f64) -> DVec3 {
+    system
+        .bodies()
+        .fold(DVec3::zero(), |acc, body| acc + gravity_from_body_at(body, point, dt))
+}
+
+/// Radio de la esfera de influencia (Laplace) de un cuerpo que orbita a otro
+pub fn sphere_of_influence(body: &CelestialBody, parent_mu: f64) -> f64 {
+    if parent_mu <= 0.0 {
+        return f64::INFINITY;
+    }
+    body.orbit_radius * (body.mu / parent_mu).powf(0.4)
+}
+
+/// Índice (como en `SolarSystem::body`) del cuerpo que domina la gravedad en `point`:
+/// el planeta cuya esfera de influencia lo contiene, o el sol
+pub fn dominant_body(system: &SolarSystem, point: DVec3) -> usize {
+    let sun_mu = system.sun.mu;
+
+    system
+        .planets
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.position().distance(&point) < sphere_of_influence(p, sun_mu))
+        .min_by(|(_, a), (_, b)| {
+            let da = a.position().distance(&point);
+            let db = b.position().distance(&point);
+            da.total_cmp(&db)
+        })
+        .map(|(i, _)| i + 1)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::solar_system::test_system;
+
+    #[test]
+    fn test_gravity_points_to_body_with_inverse_square() {
+        let sun = CelestialBody::new("Sun", 1.0, 100.0, 0.0, 0.0, 0.0, 0);
+
+        let g1 = gravity_from_body_at(&sun, DVec3::new(10.0, 0.0, 0.0), 0.0);
+        let g2 = gravity_from_body_at(&sun, DVec3::new(20.0, 0.0, 0.0), 0.0);
+
+        assert!(g1.x < 0.0 && g1.y == 0.0 && g1.z == 0.0);
+        assert!((g1.length() - 1.0).abs() < 1e-12);
+        assert!((g1.length() / g2.length() - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gravity_is_finite_inside_body() {
+        let sun = CelestialBody::new("Sun", 4.0, 200.0, 0.0, 0.0, 0.0, 0);
+        let g = gravity_from_body_at(&sun, DVec3::zero(), 0.0);
+        assert!(g.x.is_finite() && g.y.is_finite() && g.z.is_finite());
+    }
+
+    #[test]
+    fn test_dominant_body_uses_sphere_of_influence() {
+        let system = test_system();
+
+        // Lejos de todo: domina el sol
+        assert_eq!(dominant_body(&system, DVec3::new(0.0, 50.0, 0.0)), 0);
+
+        // Junto a PlanetC (índice 3)
+        let c = system.planets[2].position();
+        assert_eq!(dominant_body(&system, c + DVec3::new(4.0, 0.0, 0.0)), 3);
+    }
+
+    #[test]
+    fn test_n_body_sum() {
+        let system = test_system();
+        let point = DVec3::new(5.0, 7.0, -3.0);
+
+        let manual = system
+            .bodies()
+            .fold(DVec3::zero(), |acc, b| acc + gravity_from_body_at(b, point, 0.0));
+        assert_eq!(gravity_at(&system, point), manual);
+    }
+}
@@ -0,0 +1,427 @@
+use crate::math::{Vec3, DVec3, Quat};
+use crate::models::SpaceShip;
+use crate::scene::solar_system::SolarSystem;
+use super::trajectory::TrajectoryPoint;
+use std::fmt;
+
+/// Maniobra planificada sobre la trayectoria predicha.
+/// Las componentes del Δv se expresan en el marco orbital en el nodo
+#[derive(Debug, Clone, Copy)]
+pub struct ManeuverNode {
+    pub time: f64,      // segundos hasta el nodo
+    pub prograde: f64,
+    pub normal: f64,
+    pub radial: f64,
+}
+
+impl ManeuverNode {
+    pub fn new(time: f64) -> Self {
+        Self {
+            time,
+            prograde: 0.0,
+            normal: 0.0,
+            radial: 0.0,
+        }
+    }
+
+    /// Magnitud total del Δv
+    pub fn delta_v(&self) -> f64 {
+        (self.prograde * self.prograde + self.normal * self.normal + self.radial * self.radial).sqrt()
+    }
+
+    /// Δv en coordenadas de mundo dado el estado relativo al cuerpo de referencia.
+    /// prograde = dirección de la velocidad, normal = momento angular (r × v),
+    /// radial = hacia afuera dentro del plano orbital
+    pub fn delta_v_vector(&self, rel_pos: DVec3, rel_vel: DVec3) -> DVec3 {
+        let (prograde, normal, radial) = orbital_frame(rel_pos, rel_vel);
+        prograde * self.prograde + normal * self.normal + radial * self.radial
+    }
+
+    /// Duración estimada del encendido a empuje máximo (masa constante)
+    pub fn burn_time(&self, max_thrust: f32, mass: f32) -> f64 {
+        if max_thrust <= 0.0 {
+            return f64::INFINITY;
+        }
+        self.delta_v() * mass as f64 / max_thrust as f64
+    }
+}
+
+/// Ejes (prograde, normal, radial) del marco orbital
+pub fn orbital_frame(rel_pos: DVec3, rel_vel: DVec3) -> (DVec3, DVec3, DVec3) {
+    let prograde = rel_vel.normalize();
+    let mut normal = rel_pos.cross(&rel_vel).normalize();
+    if normal.length_squared() == 0.0 {
+        // Trayectoria radial: cualquier perpendicular sirve
+        normal = DVec3::new(0.0, -1.0, 0.0);
+    }
+    let radial = prograde.cross(&normal);
+    (prograde, normal, radial)
+}
+
+/// Interpola linealmente el estado predicho en `time`
+pub fn state_at(points: &[TrajectoryPoint], time: f64) -> Option<TrajectoryPoint> {
+    let first = points.first()?;
+    if time <= first.time {
+        return Some(*first);
+    }
+
+    points.windows(2).find(|w| w[1].time >= time).map(|w| {
+        let (a, b) = (w[0], w[1]);
+        let t = (time - a.time) / (b.time - a.time);
+        TrajectoryPoint {
+            time,
+            position: a.position.lerp(&b.position, t),
+            velocity: a.velocity.lerp(&b.velocity, t),
+        }
+    })
+}
+
+/// Estado del encendido automático
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BurnState {
+    Idle,
+    /// Orientando la nave hacia el vector de Δv, esperando el momento
+    Aligning,
+    /// Encendiendo; guarda el Δv que falta aplicar
+    Burning(DVec3),
+}
+
+/// Gestiona el nodo de maniobra y su ejecución automática
+pub struct ManeuverPlanner {
+    pub node: Option<ManeuverNode>,
+    pub auto_execute: bool,
+    pub state: BurnState,
+    pub turn_rate: f32,  // rad/s al orientar la nave
+    saved_flight_assist: Option<bool>, // estado de F antes del encendido, para restaurarlo
+}
+
+impl ManeuverPlanner {
+    pub fn new() -> Self {
+        Self {
+            node: None,
+            auto_execute: false,
+            state: BurnState::Idle,
+            turn_rate: 1.5,
+            saved_flight_assist: None,
+        }
+    }
+
+    /// Crea un nodo dentro de `time` segundos o elimina el existente
+    pub fn toggle_node(&mut self, time: f64) {
+        self.node = match self.node {
+            Some(_) => None,
+            None => Some(ManeuverNode::new(time)),
+        };
+        self.state = BurnState::Idle;
+    }
+
+    /// Avanza el reloj del nodo y, con auto-ejecución, orienta la nave y enciende.
+    /// `rel_pos` y `rel_vel` son el estado de la nave relativo al cuerpo de referencia.
+    /// Debe llamarse después del input y antes de integrar la nave
+    pub fn update(&mut self, dt: f32, ship: &mut SpaceShip, rel_pos: DVec3, rel_vel: DVec3) {
+        let turn_step = self.turn_rate * dt;
+        let Some(node) = self.node.as_mut() else {
+            self.end_burn(ship);
+            return;
+        };
+        node.time -= dt as f64;
+
+        if !self.auto_execute {
+            // Un nodo que ya pasó sin ejecutarse se descarta
+            if node.time < -1.0 {
+                self.node = None;
+            }
+            self.end_burn(ship);
+            return;
+        }
+
+        let burn_time = node.burn_time(ship.max_thrust, ship.mass);
+        match self.state {
+            BurnState::Idle | BurnState::Aligning => {
+                let dv = node.delta_v_vector(rel_pos, rel_vel);
+                align_ship(ship, dv, turn_step);
+                self.state = BurnState::Aligning;
+
+                // Encender centrado en el nodo: la mitad antes y la mitad después
+                if node.time <= burn_time * 0.5 {
+                    self.saved_flight_assist.get_or_insert(ship.flight_assist);
+                    ship.flight_assist = false;
+                    self.state = BurnState::Burning(dv);
+                }
+            }
+            BurnState::Burning(remaining) => {
+                let left = remaining.length();
+                let max_dv = (ship.max_thrust / ship.mass * dt) as f64;
+                if left < 1e-3 || max_dv <= 0.0 {
+                    ship.set_thrust(Vec3::zero());
+                    self.node = None;
+                    self.end_burn(ship);
+                    return;
+                }
+
+                align_ship(ship, remaining, turn_step);
+
+                // Empuje solo en la componente alineada con lo que falta
+                let dir = remaining / left;
+                let alignment = DVec3::from_vec3(ship.forward()).dot(&dir).max(0.0);
+                let throttle = (left / max_dv).min(1.0) * alignment;
+                ship.set_thrust(Vec3::new(0.0, 0.0, throttle as f32));
+
+                let applied = DVec3::from_vec3(ship.forward()) * (throttle * max_dv);
+                self.state = BurnState::Burning(remaining - applied);
+            }
+        }
+    }
+
+    /// Vuelve a reposo y devuelve el asistente de vuelo a como estaba antes del
+    /// encendido (al completar el nodo o al cancelarlo)
+    fn end_burn(&mut self, ship: &mut SpaceShip) {
+        if let Some(assist) = self.saved_flight_assist.take() {
+            ship.flight_assist = assist;
+        }
+        self.state = BurnState::Idle;
+    }
+}
+
+// Gira la nave hacia `dir` como máximo `max_angle` radianes, sin dejar rotación residual
+fn align_ship(ship: &mut SpaceShip, dir: DVec3, max_angle: f32) {
+    if dir.length_squared() == 0.0 {
+        return;
+    }
+    let target = Quat::look_rotation(dir.to_vec3(), ship.up());
+    let angle = ship.orientation.angle_to(&target);
+    let step = (max_angle / angle.max(1e-6)).min(1.0);
+
+    ship.orientation = ship.orientation.slerp(&target, step).normalize();
+    ship.angular_velocity = Vec3::zero();
+    ship.set_torque(Vec3::zero());
+}
+
+/// Transferencia de Hohmann entre dos órbitas circulares coplanares
+#[derive(Debug, Clone, Copy)]
+pub struct HohmannTransfer {
+    pub dv_departure: f64,  // prograde (negativo = retrógrado) al salir
+    pub dv_arrival: f64,    // prograde (negativo = retrógrado) al llegar
+    pub transfer_time: f64, // media elipse
+    pub phase_angle: f64,   // ventaja angular que debe llevar el destino al salir
+}
+
+impl HohmannTransfer {
+    /// Δv total
+    pub fn total_delta_v(&self) -> f64 {
+        self.dv_departure.abs() + self.dv_arrival.abs()
+    }
+}
+
+impl fmt::Display for HohmannTransfer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Δv {:.2} + {:.2} = {:.2}, viaje {:.1}s",
+            self.dv_departure,
+            self.dv_arrival,
+            self.total_delta_v(),
+            self.transfer_time
+        )
+    }
+}
+
+/// Transferencia de Hohmann de radio `r1` a `r2` alrededor de un cuerpo de parámetro `mu`
+pub fn hohmann_transfer(mu: f64, r1: f64, r2: f64) -> HohmannTransfer {
+    let a = (r1 + r2) * 0.5;
+
+    let dv_departure = (mu / r1).sqrt() * ((2.0 * r2 / (r1 + r2)).sqrt() - 1.0);
+    let dv_arrival = (mu / r2).sqrt() * (1.0 - (2.0 * r1 / (r1 + r2)).sqrt());
+
+    let transfer_time = std::f64::consts::PI * (a.powi(3) / mu).sqrt();
+    let n2 = (mu / r2.powi(3)).sqrt();
+    let phase_angle = std::f64::consts::PI - n2 * transfer_time;
+
+    HohmannTransfer {
+        dv_departure,
+        dv_arrival,
+        transfer_time,
+        phase_angle,
+    }
+}
+
+/// Hohmann entre las órbitas de dos planetas (índices de `SolarSystem::body`)
+/// y tiempo de espera hasta la próxima ventana. `None` si algún índice es el sol
+pub fn hohmann_between(system: &SolarSystem, from: usize, to: usize) -> Option<(HohmannTransfer, f64)> {
+    if from == 0 || to == 0 || from == to {
+        return None;
+    }
+    let origin = system.body(from)?;
+    let target = system.body(to)?;
+
+    let transfer = hohmann_transfer(system.sun.mu, origin.orbit_radius, target.orbit_radius);
+    let wait = time_to_window(
+        target.orbit_angle - origin.orbit_angle,
+        transfer.phase_angle,
+        origin.orbit_speed,
+        target.orbit_speed,
+    );
+    Some((transfer, wait))
+}
+
+/// Tiempo hasta que la fase actual (destino - origen) alcance la fase requerida
+pub fn time_to_window(current_phase: f64, required_phase: f64, origin_rate: f64, target_rate: f64) -> f64 {
+    use std::f64::consts::TAU;
+
+    let relative_rate = target_rate - origin_rate;
+    if relative_rate.abs() < 1e-12 {
+        return f64::INFINITY;
+    }
+
+    // La fase cambia a relative_rate; buscar el primer t >= 0 con fase ≡ requerida
+    let diff = (required_phase - current_phase) / relative_rate;
+    let period = TAU / relative_rate.abs();
+    diff.rem_euclid(period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_ship;
+
+    fn circular_state() -> (DVec3, DVec3) {
+        (DVec3::new(10.0, 0.0, 0.0), DVec3::new(0.0, 0.0, 2.0))
+    }
+
+    #[test]
+    fn test_orbital_frame_directions() {
+        let (r, v) = circular_state();
+        let (prograde, normal, radial) = orbital_frame(r, v);
+
+        assert_eq!(prograde, DVec3::new(0.0, 0.0, 1.0));
+        // Órbita en el mismo sentido que los planetas: momento angular -Y
+        assert!((normal - DVec3::new(0.0, -1.0, 0.0)).length() < 1e-12);
+        // Radial hacia afuera del cuerpo
+        assert!((radial - DVec3::new(1.0, 0.0, 0.0)).length() < 1e-12);
+    }
+
+    #[test]
+    fn test_delta_v_vector() {
+        let (r, v) = circular_state();
+        let mut node = ManeuverNode::new(5.0);
+        node.prograde = 3.0;
+        node.radial = 4.0;
+
+        assert_eq!(node.delta_v(), 5.0);
+        let dv = node.delta_v_vector(r, v);
+        assert!((dv - DVec3::new(4.0, 0.0, 3.0)).length() < 1e-12);
+    }
+
+    #[test]
+    fn test_burn_time() {
+        let mut node = ManeuverNode::new(0.0);
+        node.prograde = 10.0;
+        // a = F / m = 5 → 2 s
+        assert!((node.burn_time(20.0, 4.0) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_state_at_interpolates() {
+        let points = [
+            TrajectoryPoint { time: 0.0, position: DVec3::zero(), velocity: DVec3::new(1.0, 0.0, 0.0) },
+            TrajectoryPoint { time: 2.0, position: DVec3::new(2.0, 0.0, 0.0), velocity: DVec3::new(1.0, 0.0, 0.0) },
+        ];
+        let s = state_at(&points, 0.5).unwrap();
+        assert_eq!(s.position, DVec3::new(0.5, 0.0, 0.0));
+        assert!(state_at(&points, 3.0).is_none());
+    }
+
+    #[test]
+    fn test_hohmann_leo_to_geo() {
+        // Caso clásico de libro: LEO (6678 km) a GEO (42164 km)
+        let t = hohmann_transfer(398_600.0, 6678.0, 42_164.0);
+        assert!((t.dv_departure - 2.42).abs() < 0.01);
+        assert!((t.dv_arrival - 1.46).abs() < 0.01);
+        assert!((t.transfer_time / 3600.0 - 5.26).abs() < 0.02);
+        assert!((t.total_delta_v() - 3.88).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_hohmann_inward_is_retrograde() {
+        let t = hohmann_transfer(100.0, 20.0, 10.0);
+        assert!(t.dv_departure < 0.0);
+        assert!(t.dv_arrival < 0.0);
+    }
+
+    #[test]
+    fn test_time_to_window() {
+        // Destino 10° adelante, se necesitan 40°, gana 1°/s: 30 s
+        let deg = std::f64::consts::PI / 180.0;
+        let wait = time_to_window(10.0 * deg, 40.0 * deg, 1.0 * deg, 2.0 * deg);
+        assert!((wait - 30.0).abs() < 1e-9);
+
+        // Si ya pasó la ventana, esperar al siguiente periodo sinódico
+        let wait = time_to_window(50.0 * deg, 40.0 * deg, 1.0 * deg, 2.0 * deg);
+        assert!((wait - 350.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_auto_execute_delivers_delta_v() {
+        let mut ship = test_ship();
+        ship.flight_assist = false;
+        let (r, v) = circular_state();
+        ship.velocity = v;
+
+        let mut planner = ManeuverPlanner::new();
+        planner.auto_execute = true;
+        planner.toggle_node(2.0);
+        planner.node.as_mut().unwrap().prograde = 6.0;
+
+        let dt = 1.0 / 60.0;
+        let start_vel = ship.velocity;
+        for _ in 0..600 {
+            if planner.node.is_none() {
+                break;
+            }
+            let rel_vel = ship.velocity;
+            planner.update(dt, &mut ship, r, rel_vel);
+            ship.update(dt, DVec3::zero());
+        }
+
+        assert!(planner.node.is_none());
+        let delta = ship.velocity - start_vel;
+        // Prograde = +Z en este estado
+        assert!((delta.z - 6.0).abs() < 0.1, "{:?}", delta);
+        assert!(delta.x.abs() < 0.1 && delta.y.abs() < 0.1);
+    }
+
+    #[test]
+    fn test_burn_restores_flight_assist() {
+        let (r, v) = circular_state();
+        let run = |cancel: bool| {
+            let mut ship = test_ship();
+            ship.flight_assist = true;
+            ship.velocity = v;
+
+            let mut planner = ManeuverPlanner::new();
+            planner.auto_execute = true;
+            planner.toggle_node(0.0);
+            planner.node.as_mut().unwrap().prograde = 6.0;
+
+            planner.update(1.0 / 60.0, &mut ship, r, v);
+            assert!(matches!(planner.state, BurnState::Burning(_)));
+            assert!(!ship.flight_assist);
+
+            if cancel {
+                planner.toggle_node(0.0);
+            }
+            for _ in 0..600 {
+                let rel_vel = ship.velocity;
+                planner.update(1.0 / 60.0, &mut ship, r, rel_vel);
+                if planner.node.is_none() {
+                    break;
+                }
+            }
+            assert_eq!(planner.state, BurnState::Idle);
+            ship.flight_assist
+        };
+
+        assert!(run(false), "al completar el nodo");
+        assert!(run(true), "al cancelarlo");
+    }
+}
@@ -1,3 +1,15 @@
+pub mod bvh;
 pub mod collision;
+pub mod gravity;
+pub mod orbit;
+pub mod picking;
+pub mod trajectory;
+pub mod maneuver;
 
+pub use bvh::*;
 pub use collision::*;
+pub use gravity::*;
+pub use orbit::*;
+pub use picking::*;
+pub use trajectory::*;
+pub use maneuver::*;
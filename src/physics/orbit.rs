@@ -0,0 +1,166 @@
+use crate::math::DVec3;
+use std::fmt;
+
+/// Normal del plano del sistema: los planetas giran con momento angular -Y,
+/// así que las órbitas en su mismo sentido tienen inclinación 0
+const SYSTEM_NORMAL: DVec3 = DVec3 { x: 0.0, y: -1.0, z: 0.0 };
+
+/// Elementos orbitales (cónica de dos cuerpos) de un estado relativo al cuerpo central.
+/// Las distancias se miden desde el centro del cuerpo
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitalElements {
+    #[allow(dead_code)]
+    pub semi_major_axis: f64,  // negativo en órbitas hiperbólicas
+    pub eccentricity: f64,
+    pub inclination: f64,      // radianes, respecto al plano del sistema
+    pub periapsis: f64,
+    pub apoapsis: Option<f64>, // None si la órbita es abierta
+    pub period: Option<f64>,   // segundos, None si la órbita es abierta
+}
+
+impl OrbitalElements {
+    /// Calcula los elementos desde posición y velocidad relativas al cuerpo central
+    pub fn from_state(r: DVec3, v: DVec3, mu: f64) -> Self {
+        let r_len = r.length();
+        let h = r.cross(&v);
+        let h_len = h.length();
+
+        // Vector excentricidad: apunta al periapsis
+        let e_vec = v.cross(&h) / mu - r / r_len;
+        let eccentricity = e_vec.length();
+
+        let energy = v.length_squared() * 0.5 - mu / r_len;
+        let semi_major_axis = if energy.abs() > 1e-12 {
+            -mu / (2.0 * energy)
+        } else {
+            f64::INFINITY
+        };
+
+        let inclination = if h_len > 0.0 {
+            (h.dot(&SYSTEM_NORMAL) / h_len).clamp(-1.0, 1.0).acos()
+        } else {
+            0.0
+        };
+
+        // p = h² / μ vale para cualquier cónica (también radial con h = 0)
+        let periapsis = if h_len > 0.0 {
+            h_len * h_len / (mu * (1.0 + eccentricity))
+        } else {
+            0.0
+        };
+
+        let bound = eccentricity < 1.0 && energy < 0.0;
+        let apoapsis = bound.then_some(semi_major_axis * (1.0 + eccentricity));
+        let period = bound
+            .then(|| 2.0 * std::f64::consts::PI * (semi_major_axis.powi(3) / mu).sqrt());
+
+        Self {
+            semi_major_axis,
+            eccentricity,
+            inclination,
+            periapsis,
+            apoapsis,
+            period,
+        }
+    }
+}
+
+impl fmt::Display for OrbitalElements {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.apoapsis, self.period) {
+            (Some(ap), Some(t)) => write!(
+                f,
+                "Ap {:.1} Pe {:.1} i {:.1}° T {:.1}s",
+                ap,
+                self.periapsis,
+                self.inclination.to_degrees(),
+                t
+            ),
+            _ => write!(
+                f,
+                "escape Pe {:.1} e {:.2} i {:.1}°",
+                self.periapsis,
+                self.eccentricity,
+                self.inclination.to_degrees()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MU: f64 = 20.0;
+
+    /// Velocidad prograda (mismo sentido que los planetas) en la posición `r`
+    fn prograde(r: DVec3, speed: f64) -> DVec3 {
+        SYSTEM_NORMAL.cross(&r).normalize() * speed
+    }
+
+    #[test]
+    fn test_circular_orbit() {
+        let r = DVec3::new(6.0, 0.0, 0.0);
+        let v = prograde(r, (MU / 6.0).sqrt());
+        let el = OrbitalElements::from_state(r, v, MU);
+
+        assert!(el.eccentricity < 1e-9);
+        assert!((el.semi_major_axis - 6.0).abs() < 1e-9);
+        assert!((el.periapsis - 6.0).abs() < 1e-9);
+        assert!((el.apoapsis.unwrap() - 6.0).abs() < 1e-9);
+        assert!(el.inclination.abs() < 1e-6);
+
+        let expected_period = 2.0 * std::f64::consts::PI * (216.0 / MU).sqrt();
+        assert!((el.period.unwrap() - expected_period).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_planets_are_prograde() {
+        // Mismo sentido de giro que CelestialBody::velocity
+        let r = DVec3::new(24.0, 0.0, 0.0);
+        let v = DVec3::new(0.0, 0.0, 2.4);
+        let el = OrbitalElements::from_state(r, v, 200.0);
+        assert!(el.inclination.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_elliptical_orbit_apsides() {
+        // En periapsis r = 5, con apoapsis deseado 15: a = 10
+        let (rp, ra) = (5.0, 15.0);
+        let a = (rp + ra) / 2.0;
+        let vp = (MU * (2.0 / rp - 1.0 / a)).sqrt();
+
+        let r = DVec3::new(0.0, 0.0, rp);
+        let el = OrbitalElements::from_state(r, prograde(r, vp), MU);
+
+        assert!((el.periapsis - rp).abs() < 1e-9);
+        assert!((el.apoapsis.unwrap() - ra).abs() < 1e-9);
+        assert!((el.eccentricity - 0.5).abs() < 1e-9);
+        assert!((el.semi_major_axis - a).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_inclination() {
+        // Velocidad sobre el eje Y: órbita polar
+        let r = DVec3::new(6.0, 0.0, 0.0);
+        let v = DVec3::new(0.0, (MU / 6.0).sqrt(), 0.0);
+        let el = OrbitalElements::from_state(r, v, MU);
+        assert!((el.inclination.to_degrees() - 90.0).abs() < 1e-6);
+
+        // Retrógrada
+        let el = OrbitalElements::from_state(r, prograde(r, 1.0) * -1.0, MU);
+        assert!((el.inclination.to_degrees() - 180.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_escape_trajectory() {
+        let r = DVec3::new(6.0, 0.0, 0.0);
+        let escape = (2.0 * MU / 6.0).sqrt();
+        let el = OrbitalElements::from_state(r, prograde(r, escape * 1.5), MU);
+
+        assert!(el.eccentricity > 1.0);
+        assert!(el.apoapsis.is_none());
+        assert!(el.period.is_none());
+        assert!((el.periapsis - 6.0).abs() < 1e-9);
+    }
+}
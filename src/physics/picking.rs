@@ -0,0 +1,91 @@
+use crate::math::{screen_to_world, DVec3, Mat4};
+
+/// Rayo en coordenadas de mundo (f64, como las posiciones de los cuerpos)
+#[derive(Debug, Clone, Copy)]
+pub struct PickRay {
+    pub origin: DVec3,
+    pub direction: DVec3, // normalizada
+}
+
+/// Cuerpo alcanzado por un rayo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickHit {
+    pub body: usize,   // índice como en `SolarSystem::body`
+    pub point: DVec3,  // punto de la superficie
+    pub distance: f64, // desde el origen del rayo
+}
+
+impl PickRay {
+    pub fn new(origin: DVec3, direction: DVec3) -> Self {
+        Self { origin, direction: direction.normalize() }
+    }
+
+    /// Rayo que sale de la cámara por el píxel (`x`, `y`).
+    /// `view_proj` es relativa a la cámara (vista sin traslación), como en el render
+    pub fn from_screen(
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        view_proj: &Mat4,
+        camera_pos: DVec3,
+    ) -> Option<Self> {
+        let inverse = view_proj.inverse()?;
+        // Profundidad NDC intermedia: cae delante de la cámara con Z estándar y reversed-Z
+        let through = screen_to_world(x, y, 0.5, &inverse, width, height)?;
+        if through.length_squared() == 0.0 {
+            return None;
+        }
+        Some(Self::new(camera_pos, DVec3::from_vec3(through)))
+    }
+
+    pub fn at(&self, t: f64) -> DVec3 {
+        self.origin + self.direction * t
+    }
+
+    /// Distancia al primer punto de una esfera, si está delante del rayo
+    pub fn intersect_sphere(&self, center: DVec3, radius: f64) -> Option<f64> {
+        let m = self.origin - center;
+        let b = m.dot(&self.direction);
+        let c = m.length_squared() - radius * radius;
+        if c > 0.0 && b > 0.0 {
+            return None;
+        }
+        let disc = b * b - c;
+        if disc < 0.0 {
+            return None;
+        }
+        Some((-b - disc.sqrt()).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec3;
+
+    #[test]
+    fn test_sphere_hit_and_miss() {
+        let ray = PickRay::new(DVec3::new(0.0, 0.0, -10.0), DVec3::new(0.0, 0.0, 3.0));
+        assert_eq!(ray.intersect_sphere(DVec3::zero(), 2.0), Some(8.0));
+        assert!(ray.intersect_sphere(DVec3::new(0.0, 5.0, 0.0), 2.0).is_none());
+        // Detrás del rayo
+        assert!(ray.intersect_sphere(DVec3::new(0.0, 0.0, -20.0), 2.0).is_none());
+    }
+
+    #[test]
+    fn test_center_pixel_looks_forward() {
+        let forward = Vec3::new(1.0, 0.5, -2.0).normalize();
+        let view = Mat4::look_at(Vec3::zero(), forward, Vec3::unit_y());
+        let camera = DVec3::new(1e6, 0.0, -3e5);
+
+        for projection in [
+            Mat4::perspective(1.0, 16.0 / 9.0, 0.1, 1000.0),
+            Mat4::perspective_reversed_z(1.0, 16.0 / 9.0, 0.1, 1000.0),
+        ] {
+            let ray = PickRay::from_screen(640.0, 360.0, 1280.0, 720.0, &(projection * view), camera).unwrap();
+            assert_eq!(ray.origin, camera);
+            assert!((ray.direction.to_vec3() - forward).length() < 1e-4);
+        }
+    }
+}
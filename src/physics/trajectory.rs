@@ -0,0 +1,136 @@
+use crate::math::DVec3;
+use crate::scene::solar_system::SolarSystem;
+use crate::scene::celestial_body::CelestialBody;
+use super::gravity::gravity_at_time;
+
+/// Estado predicho de la nave `time` segundos en el futuro
+#[derive(Debug, Clone, Copy)]
+pub struct TrajectoryPoint {
+    pub time: f64,
+    pub position: DVec3,
+    pub velocity: DVec3,
+}
+
+/// Integra la trayectoria futura de la nave bajo la gravedad del sistema
+pub struct TrajectoryPredictor {
+    pub horizon: f64,  // segundos hacia adelante
+    pub step: f64,     // paso de integración
+}
+
+impl TrajectoryPredictor {
+    pub fn new(horizon: f64, step: f64) -> Self {
+        Self { horizon, step }
+    }
+
+    /// Predice desde el estado actual
+    pub fn predict(&self, system: &SolarSystem, position: DVec3, velocity: DVec3) -> Vec<TrajectoryPoint> {
+        self.predict_from(system, TrajectoryPoint { time: 0.0, position, velocity })
+    }
+
+    /// Predice desde un estado futuro (p. ej. después de una maniobra) hasta `horizon`.
+    /// Usa velocity Verlet y se detiene si la trayectoria choca con un cuerpo
+    pub fn predict_from(&self, system: &SolarSystem, start: TrajectoryPoint) -> Vec<TrajectoryPoint> {
+        let mut points = vec![start];
+        if self.step <= 0.0 {
+            return points;
+        }
+
+        let TrajectoryPoint { mut time, mut position, mut velocity } = start;
+        let mut accel = gravity_at_time(system, position, time);
+
+        while time < self.horizon {
+            let dt = self.step.min(self.horizon - time);
+
+            position = position + velocity * dt + accel * (0.5 * dt * dt);
+            time += dt;
+            let next_accel = gravity_at_time(system, position, time);
+            velocity = velocity + (accel + next_accel) * (0.5 * dt);
+            accel = next_accel;
+
+            points.push(TrajectoryPoint { time, position, velocity });
+
+            let hit = system
+                .bodies()
+                .any(|b| b.position_at(time).distance(&position) < b.radius as f64);
+            if hit {
+                break;
+            }
+        }
+
+        points
+    }
+}
+
+/// Reexpresa la trayectoria en el marco del cuerpo: cada punto se mueve con el
+/// cuerpo hasta su posición actual. Así una órbita alrededor de un planeta
+/// en movimiento se ve como una elipse cerrada y no como una espiral
+pub fn relative_to_body(points: &[TrajectoryPoint], body: &CelestialBody) -> Vec<DVec3> {
+    let now = body.position();
+    points
+        .iter()
+        .map(|p| p.position - body.position_at(p.time) + now)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::solar_system::test_system;
+
+
+    #[test]
+    fn test_prediction_covers_horizon() {
+        let system = test_system();
+        let predictor = TrajectoryPredictor::new(10.0, 0.1);
+        let points = predictor.predict(&system, DVec3::new(0.0, 60.0, 0.0), DVec3::zero());
+
+        assert_eq!(points[0].time, 0.0);
+        assert!((points.last().unwrap().time - 10.0).abs() < 1e-9);
+        assert!((points.len() as i64 - 101).abs() <= 1);
+    }
+
+    #[test]
+    fn test_prediction_falls_toward_sun() {
+        let system = test_system();
+        let predictor = TrajectoryPredictor::new(5.0, 0.05);
+        let start = DVec3::new(0.0, 40.0, 0.0);
+        let points = predictor.predict(&system, start, DVec3::zero());
+
+        let end = points.last().unwrap().position;
+        assert!(end.length() < start.length());
+        assert!(end.x.abs() < 1.0 && end.z.abs() < 1.0);
+    }
+
+    #[test]
+    fn test_prediction_stops_on_impact() {
+        let system = test_system();
+        let predictor = TrajectoryPredictor::new(60.0, 0.05);
+        // Directo hacia el sol
+        let points = predictor.predict(&system, DVec3::new(0.0, 30.0, 0.0), DVec3::new(0.0, -20.0, 0.0));
+
+        let last = points.last().unwrap();
+        assert!(last.time < 60.0);
+        assert!(last.position.length() < system.sun.radius as f64 + 1.0);
+    }
+
+    #[test]
+    fn test_circular_orbit_closes_in_body_frame() {
+        let system = test_system();
+        let planet = &system.planets[2];
+        let r = 4.0;
+        let speed = (planet.mu / r).sqrt();
+
+        let start_pos = planet.position() + DVec3::new(r, 0.0, 0.0);
+        let start_vel = planet.velocity() + DVec3::new(0.0, 0.0, speed);
+        let period = std::f64::consts::TAU * (r * r * r / planet.mu).sqrt();
+
+        let predictor = TrajectoryPredictor::new(period, 0.01);
+        let points = predictor.predict(&system, start_pos, start_vel);
+        let rel = relative_to_body(&points, planet);
+
+        // Perturbado por el sol, pero debe quedar cerca de la órbita inicial
+        for p in &rel {
+            assert!((p.distance(&planet.position()) - r).abs() < 1.0);
+        }
+    }
+}
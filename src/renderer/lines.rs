@@ -0,0 +1,212 @@
+use crate::math::{Vec3, Vec4, Mat4, smooth_step};
+use crate::framebuffer::{Framebuffer, lerp_color};
+
+/// Punto en pantalla (x, y, z de NDC)
+type ScreenPoint = (f32, f32, f32);
+
+/// Desvanecimiento de líneas con la distancia a la cámara
+#[derive(Debug, Clone, Copy)]
+pub struct LineFade {
+    pub start: f32,  // distancia donde empieza a desvanecerse
+    pub end: f32,    // distancia donde desaparece
+}
+
+impl LineFade {
+    /// Intensidad en [0, 1] para un segmento a `distance` de la cámara
+    pub fn factor(&self, distance: f32) -> f32 {
+        if self.end <= self.start {
+            return 1.0;
+        }
+        1.0 - smooth_step((distance - self.start) / (self.end - self.start))
+    }
+}
+
+/// Dibuja una polilínea 3D con depth test.
+/// `points` son relativos a la cámara y `view_proj` no lleva traslación.
+/// Los segmentos se recortan contra el plano cercano (w = `near`) en clip space,
+/// así un punto detrás de la cámara nunca se proyecta
+pub fn draw_polyline_3d(
+    fb: &mut Framebuffer,
+    points: &[Vec3],
+    view_proj: &Mat4,
+    near: f32,
+    color: u32,
+    fade: LineFade,
+) {
+    let clip: Vec<Vec4> = points
+        .iter()
+        .map(|p| view_proj.mul_vec4(Vec4::from_point(*p)))
+        .collect();
+
+    for i in 1..points.len() {
+        let midpoint = (points[i - 1] + points[i]) * 0.5;
+        let intensity = fade.factor(midpoint.length());
+        if intensity <= 0.0 {
+            continue;
+        }
+
+        let Some((a, b)) = clip_near(clip[i - 1], clip[i], near) else {
+            continue;
+        };
+
+        let w = fb.width as f32;
+        let h = fb.height as f32;
+        let (ax, ay, az) = to_screen(a, w, h);
+        let (bx, by, bz) = to_screen(b, w, h);
+
+        let Some(((ax, ay, az), (bx, by, bz))) = clip_screen((ax, ay, az), (bx, by, bz), w - 1.0, h - 1.0) else {
+            continue;
+        };
+
+        let c = lerp_color(color, 0xFF000000, 1.0 - intensity);
+        fb.draw_line(
+            ax.round() as i32, ay.round() as i32, az,
+            bx.round() as i32, by.round() as i32, bz,
+            c,
+        );
+    }
+}
+
+/// Recorta un segmento en clip space contra el plano w = near
+fn clip_near(a: Vec4, b: Vec4, near: f32) -> Option<(Vec4, Vec4)> {
+    let a_in = a.w >= near;
+    let b_in = b.w >= near;
+
+    match (a_in, b_in) {
+        (true, true) => Some((a, b)),
+        (false, false) => None,
+        _ => {
+            let t = (near - a.w) / (b.w - a.w);
+            let p = a + (b - a) * t;
+            if a_in { Some((a, p)) } else { Some((p, b)) }
+        }
+    }
+}
+
+fn to_screen(c: Vec4, width: f32, height: f32) -> ScreenPoint {
+    let ndc = c.to_vec3();
+    (
+        (ndc.x + 1.0) * 0.5 * width,
+        (1.0 - ndc.y) * 0.5 * height,
+        ndc.z,
+    )
+}
+
+/// Liang-Barsky contra el rectángulo [0, max_x] x [0, max_y], interpolando z.
+/// Evita recorrer con Bresenham segmentos que se salen mucho de la pantalla
+fn clip_screen(
+    a: ScreenPoint,
+    b: ScreenPoint,
+    max_x: f32,
+    max_y: f32,
+) -> Option<(ScreenPoint, ScreenPoint)> {
+    let dx = b.0 - a.0;
+    let dy = b.1 - a.1;
+    let mut t0 = 0.0_f32;
+    let mut t1 = 1.0_f32;
+
+    for (p, q) in [(-dx, a.0), (dx, max_x - a.0), (-dy, a.1), (dy, max_y - a.1)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let r = q / p;
+            if p < 0.0 {
+                t0 = t0.max(r);
+            } else {
+                t1 = t1.min(r);
+            }
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    let lerp = |t: f32| (a.0 + dx * t, a.1 + dy * t, a.2 + (b.2 - a.2) * t);
+    Some((lerp(t0), lerp(t1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::DepthMode;
+    use crate::math::Mat4;
+
+    const NEAR: f32 = 0.1;
+    const NO_FADE: LineFade = LineFade { start: 0.0, end: 0.0 };
+
+    fn setup() -> (Framebuffer, Mat4) {
+        let mut fb = Framebuffer::new(64, 64);
+        fb.set_depth_mode(DepthMode::ReversedZ);
+        let proj = Mat4::perspective_reversed_z(1.2, 1.0, NEAR, 1000.0);
+        (fb, proj)
+    }
+
+    fn lit_pixels(fb: &Framebuffer) -> usize {
+        fb.buffer.iter().filter(|&&c| c != 0).count()
+    }
+
+    #[test]
+    fn test_segment_fully_behind_camera_is_skipped() {
+        let (mut fb, proj) = setup();
+        // La cámara mira hacia -Z: +Z está detrás
+        let points = [Vec3::new(-5.0, 0.0, 10.0), Vec3::new(5.0, 0.0, 10.0)];
+        draw_polyline_3d(&mut fb, &points, &proj, NEAR, 0xFFFFFFFF, NO_FADE);
+        assert_eq!(lit_pixels(&fb), 0);
+    }
+
+    #[test]
+    fn test_segment_crossing_near_plane_is_clipped() {
+        let (mut fb, proj) = setup();
+        // Va de delante de la cámara a detrás: solo debe verse la mitad delantera,
+        // que se proyecta en la mitad inferior de la pantalla (está por debajo del ojo)
+        let points = [Vec3::new(0.0, -1.0, -10.0), Vec3::new(0.0, -1.0, 10.0)];
+        draw_polyline_3d(&mut fb, &points, &proj, NEAR, 0xFFFFFFFF, NO_FADE);
+
+        assert!(lit_pixels(&fb) > 0);
+        for y in 0..32 {
+            for x in 0..64 {
+                assert_eq!(fb.get_pixel(x, y), Some(0), "píxel basura en ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_line_is_depth_tested() {
+        let (mut fb, proj) = setup();
+        // Un "planeta" a distancia 5 tapa toda la pantalla
+        fb.zbuffer.fill(proj.mul_vec4(Vec4::new(0.0, 0.0, -5.0, 1.0)).to_vec3().z);
+
+        let behind = [Vec3::new(-1.0, 0.0, -20.0), Vec3::new(1.0, 0.0, -20.0)];
+        draw_polyline_3d(&mut fb, &behind, &proj, NEAR, 0xFFFFFFFF, NO_FADE);
+        assert_eq!(lit_pixels(&fb), 0);
+
+        let in_front = [Vec3::new(-1.0, 0.0, -2.0), Vec3::new(1.0, 0.0, -2.0)];
+        draw_polyline_3d(&mut fb, &in_front, &proj, NEAR, 0xFFFFFFFF, NO_FADE);
+        assert!(lit_pixels(&fb) > 0);
+    }
+
+    #[test]
+    fn test_fade_with_distance() {
+        let fade = LineFade { start: 10.0, end: 20.0 };
+        assert_eq!(fade.factor(5.0), 1.0);
+        assert_eq!(fade.factor(25.0), 0.0);
+        assert!(fade.factor(15.0) > 0.0 && fade.factor(15.0) < 1.0);
+
+        let (mut fb, proj) = setup();
+        let far = [Vec3::new(-50.0, 0.0, -30.0), Vec3::new(50.0, 0.0, -30.0)];
+        draw_polyline_3d(&mut fb, &far, &proj, NEAR, 0xFFFFFFFF, fade);
+        assert_eq!(lit_pixels(&fb), 0);
+    }
+
+    #[test]
+    fn test_offscreen_segment_is_trimmed() {
+        let (mut fb, proj) = setup();
+        // Segmento enorme que cruza la pantalla
+        let points = [Vec3::new(-1.0e5, 0.0, -10.0), Vec3::new(1.0e5, 0.0, -10.0)];
+        draw_polyline_3d(&mut fb, &points, &proj, NEAR, 0xFFFFFFFF, NO_FADE);
+        assert_eq!(lit_pixels(&fb), 64);
+    }
+}
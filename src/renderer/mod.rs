@@ -1,5 +1,4 @@
 pub mod rasterizer;
 pub mod pipeline;
-
-pub use rasterizer::*;
-pub use pipeline::*;
+pub mod lines;
+pub mod transparent;
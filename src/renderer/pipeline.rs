@@ -1,12 +1,14 @@
 use crate::math::{Vec3, Vec4, Mat4};
-use crate::renderer::rasterizer::draw_filled_triangle;
-use crate::framebuffer::Framebuffer;
+use crate::renderer::rasterizer::{draw_filled_triangle, draw_shaded_triangle};
+use crate::framebuffer::{Framebuffer, DepthMode, BlendMode};
 
 pub struct Pipeline {
     pub mvp: Mat4,
     pub color: u32,
     pub fb_width: f32,
     pub fb_height: f32,
+    pub depth_mode: DepthMode,
+    pub blend_mode: BlendMode,
 }
 
 impl Pipeline {
@@ -16,6 +18,22 @@ impl Pipeline {
             color: 0xFFFFFFFF,
             fb_width: width as f32,
             fb_height: height as f32,
+            depth_mode: DepthMode::Standard,
+            blend_mode: BlendMode::Opaque,
+        }
+    }
+
+    /// Selecciona la convención de profundidad. El framebuffer debe usar la misma
+    pub fn set_depth_mode(&mut self, mode: DepthMode) {
+        self.depth_mode = mode;
+    }
+
+    /// Proyección en perspectiva acorde al modo de profundidad activo
+    pub fn projection_matrix(&self, fov: f32, near: f32, far: f32) -> Mat4 {
+        let aspect = self.fb_width / self.fb_height;
+        match self.depth_mode {
+            DepthMode::Standard => Mat4::perspective(fov, aspect, near, far),
+            DepthMode::ReversedZ => Mat4::perspective_reversed_z(fov, aspect, near, far),
         }
     }
 
@@ -27,13 +45,25 @@ impl Pipeline {
         self.color = color;
     }
 
+    /// Modo de mezcla de lo que se dibuje a continuación. Fuera de `Opaque`
+    /// el alfa de `color` (0xAARRGGBB) es la opacidad
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
     /// Dibuja una malla completa (triángulos)
     pub fn draw_mesh(
         &self,
         fb: &mut Framebuffer,
-        vertices: &Vec<Vec3>,
-        faces: &Vec<(usize, usize, usize)>
+        vertices: &[Vec3],
+        faces: &[(usize, usize, usize)]
     ) {
+        if self.blend_mode != BlendMode::Opaque {
+            let (color, alpha) = (self.color, (self.color >> 24) as f32 / 255.0);
+            self.draw_mesh_shaded(fb, vertices, faces, true, |_| Some((color, alpha)));
+            return;
+        }
+
         for (i0, i1, i2) in faces {
             let v0 = vertices[*i0];
             let v1 = vertices[*i1];
@@ -49,8 +79,37 @@ impl Pipeline {
         }
     }
 
+    /// Dibuja una malla con color por píxel: `shade` recibe el punto en el espacio
+    /// del modelo y devuelve color y alfa (o `None` para no dibujar ese píxel),
+    /// que se combinan con el modo de mezcla activo
+    pub fn draw_mesh_shaded(
+        &self,
+        fb: &mut Framebuffer,
+        vertices: &[Vec3],
+        faces: &[(usize, usize, usize)],
+        cull_backfaces: bool,
+        mut shade: impl FnMut(Vec3) -> Option<(u32, f32)>,
+    ) {
+        for &(i0, i1, i2) in faces {
+            let (v0, v1, v2) = (vertices[i0], vertices[i1], vertices[i2]);
+
+            if let (Some(a), Some(b), Some(c)) = (self.project_w(v0), self.project_w(v1), self.project_w(v2)) {
+                let tri = [(a.0, a.1, a.2), (b.0, b.1, b.2), (c.0, c.1, c.2)];
+                draw_shaded_triangle(fb, tri, [a.3, b.3, c.3], cull_backfaces, self.blend_mode, |[u, v, w]| {
+                    shade(v0 * u + v1 * v + v2 * w)
+                });
+            }
+        }
+    }
+
     #[inline]
     fn project(&self, p: Vec3) -> Option<(f32,f32,f32)> {
+        self.project_w(p).map(|(x, y, z, _)| (x, y, z))
+    }
+
+    /// Como `project`, pero también devuelve 1/w de clip
+    #[inline]
+    fn project_w(&self, p: Vec3) -> Option<(f32, f32, f32, f32)> {
         let clip = self.mvp.mul_vec4(Vec4::from_point(p));
 
         if clip.w <= 0.0 {
@@ -64,6 +123,94 @@ impl Pipeline {
         let sx = (ndc_x + 1.0) * 0.5 * self.fb_width;
         let sy = (1.0 - ndc_y) * 0.5 * self.fb_height;
 
-        Some((sx, sy, ndc_z))
+        Some((sx, sy, ndc_z, 1.0 / clip.w))
+    }
+}
+
+/// Framebuffer y pipeline con Z invertido y el color en negro: la base de los
+/// tests de render
+#[cfg(test)]
+pub(crate) fn black_frame(width: usize, height: usize) -> (Framebuffer, Pipeline) {
+    let mut fb = Framebuffer::new(width, height);
+    let mut pipeline = Pipeline::new(width, height);
+    fb.set_depth_mode(DepthMode::ReversedZ);
+    pipeline.set_depth_mode(DepthMode::ReversedZ);
+    fb.clear(0xFF000000);
+    (fb, pipeline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEAR: f32 = 0.1;
+    const FAR: f32 = 1_000_000.0;
+
+    fn depth_at(pipeline: &Pipeline, distance: f32) -> f32 {
+        let mut p = Pipeline::new(100, 100);
+        p.set_depth_mode(pipeline.depth_mode);
+        p.set_mvp(pipeline.projection_matrix(1.0, NEAR, FAR));
+        p.project(Vec3::new(0.0, 0.0, -distance)).unwrap().2
+    }
+
+    fn pipeline(mode: DepthMode) -> Pipeline {
+        let mut p = Pipeline::new(100, 100);
+        p.set_depth_mode(mode);
+        p
+    }
+
+    #[test]
+    fn test_reversed_z_range() {
+        let p = pipeline(DepthMode::ReversedZ);
+        assert!((depth_at(&p, NEAR) - 1.0).abs() < 1e-4);
+        assert!(depth_at(&p, FAR).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_standard_depth_collapses_far_away() {
+        // A 100 000 unidades, 100 de separación son indistinguibles en f32
+        let p = pipeline(DepthMode::Standard);
+        assert_eq!(depth_at(&p, 100_000.0), depth_at(&p, 100_100.0));
+    }
+
+    #[test]
+    fn test_reversed_z_precision_at_large_distances() {
+        let p = pipeline(DepthMode::ReversedZ);
+        let mut previous = f32::INFINITY;
+
+        // Separaciones del 0.1% siguen siendo ordenables hasta casi el plano lejano
+        let mut d = 10.0;
+        while d < FAR * 0.5 {
+            let a = depth_at(&p, d);
+            let b = depth_at(&p, d * 1.001);
+            assert!(a > b, "sin precisión a distancia {}", d);
+            assert!(a < previous);
+            previous = a;
+            d *= 4.0;
+        }
+    }
+
+    #[test]
+    fn test_reversed_z_occlusion_far_away() {
+        // Dos triángulos a 50 000 y 50 050 unidades, el lejano primero.
+        // Con z estándar ambos dan la misma profundidad y el cercano se descartaría
+        let mut fb = Framebuffer::new(64, 64);
+        fb.set_depth_mode(DepthMode::ReversedZ);
+        let mut p = Pipeline::new(64, 64);
+        p.set_depth_mode(DepthMode::ReversedZ);
+        p.set_mvp(p.projection_matrix(1.0, NEAR, FAR));
+
+        let tri = |d: f32| {
+            let s = d * 0.2;
+            vec![Vec3::new(-s, -s, -d), Vec3::new(s, -s, -d), Vec3::new(0.0, s, -d)]
+        };
+        let faces = [(0, 2, 1)];
+
+        p.set_color(0xFFFF0000);
+        p.draw_mesh(&mut fb, &tri(50_050.0), &faces);
+        p.set_color(0xFF00FF00);
+        p.draw_mesh(&mut fb, &tri(50_000.0), &faces);
+
+        assert_eq!(fb.get_pixel(32, 32), Some(0xFF00FF00));
     }
 }
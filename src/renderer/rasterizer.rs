@@ -1,4 +1,4 @@
-use crate::framebuffer::Framebuffer;
+use crate::framebuffer::{Framebuffer, BlendMode};
 use crate::math::barycentric;
 
 /// Rasteriza un triángulo lleno con z-buffer
@@ -46,3 +46,107 @@ pub fn draw_filled_triangle(
         }
     }
 }
+
+/// Rasteriza un triángulo con color por píxel. `inv_w` es 1/w de clip de cada
+/// vértice: `shade` recibe las baricéntricas con corrección de perspectiva y
+/// devuelve color y alfa, que se combinan según `blend`. En modo `Alpha` los
+/// píxeles de alfa 1 se tratan como opacos y escriben profundidad
+pub fn draw_shaded_triangle(
+    fb: &mut Framebuffer,
+    tri: [(f32, f32, f32); 3],
+    inv_w: [f32; 3],
+    cull_backfaces: bool,
+    blend: BlendMode,
+    mut shade: impl FnMut([f32; 3]) -> Option<(u32, f32)>,
+) {
+    let (x0, y0, z0) = tri[0];
+    let (x1, y1, z1) = tri[1];
+    let (x2, y2, z2) = tri[2];
+
+    let normal_z = (x1 - x0) * (y2 - y0) - (y1 - y0) * (x2 - x0);
+    if normal_z == 0.0 || (cull_backfaces && normal_z < 0.0) {
+        return;
+    }
+    // Con el triángulo en sentido positivo cada arista compartida se recorre al
+    // revés en el vecino, así la regla de desempate no mezcla dos veces un píxel
+    let sign = normal_z.signum();
+    let (a, b, c) = ((x0, y0), (x1, y1), (x2, y2));
+    let edges = [(b, c), (c, a), (a, b)];
+    let area = edge_function(a, b, c) * sign;
+
+    let min_x = x0.min(x1).min(x2).floor().max(0.0) as i32;
+    let max_x = x0.max(x1).max(x2).ceil().min(fb.width as f32 - 1.0) as i32;
+
+    let min_y = y0.min(y1).min(y2).floor().max(0.0) as i32;
+    let max_y = y0.max(y1).max(y2).ceil().min(fb.height as f32 - 1.0) as i32;
+
+    for px in min_x..=max_x {
+        for py in min_y..=max_y {
+            let p = (px as f32, py as f32);
+            let mut weights = [0.0; 3];
+            let inside = edges.iter().zip(weights.iter_mut()).all(|(&(from, to), weight)| {
+                let e = edge_function(from, to, p) * sign;
+                *weight = e / area;
+                e > 0.0 || (e == 0.0 && owns_edge(from, to, sign))
+            });
+            if !inside {
+                continue;
+            }
+            let [u, v, w] = weights;
+
+            let z = u * z0 + v * z1 + w * z2;
+            if !fb.depth_test(px as usize, py as usize, z) {
+                continue;
+            }
+
+            // baricéntricas en el espacio del modelo (corrección de perspectiva)
+            let (pu, pv, pw) = (u * inv_w[0], v * inv_w[1], w * inv_w[2]);
+            let sum = pu + pv + pw;
+            let Some((color, alpha)) = shade([pu / sum, pv / sum, pw / sum]) else {
+                continue;
+            };
+
+            let mode = if blend == BlendMode::Alpha && alpha >= 1.0 { BlendMode::Opaque } else { blend };
+            if alpha > 0.0 || mode.writes_depth() {
+                fb.blend_pixel_with_depth(px as usize, py as usize, color, alpha, z, mode);
+            }
+        }
+    }
+}
+
+/// Función de arista de `p` respecto de `from -> to`. Se evalúa siempre con los
+/// extremos en el mismo orden para que dos triángulos vecinos obtengan
+/// exactamente el valor opuesto
+fn edge_function(from: (f32, f32), to: (f32, f32), p: (f32, f32)) -> f32 {
+    let (a, b, flip) = if from <= to { (from, to, 1.0) } else { (to, from, -1.0) };
+    flip * ((b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0))
+}
+
+/// Regla de desempate para píxeles justo sobre una arista: de los dos triángulos
+/// que la comparten, solo uno se queda con ellos
+fn owns_edge(from: (f32, f32), to: (f32, f32), sign: f32) -> bool {
+    let (dx, dy) = ((to.0 - from.0) * sign, (to.1 - from.1) * sign);
+    dy > 0.0 || (dy == 0.0 && dx < 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_edge_is_blended_once() {
+        let mut fb = Framebuffer::new(8, 8);
+        fb.clear(0xFF000000);
+
+        // Un cuadrado partido en diagonal: los píxeles de la diagonal caen justo
+        // sobre la arista compartida
+        let quad = [(1.0, 1.0, 0.5), (6.0, 1.0, 0.5), (6.0, 6.0, 0.5), (1.0, 6.0, 0.5)];
+        for tri in [[quad[0], quad[1], quad[2]], [quad[0], quad[2], quad[3]]] {
+            draw_shaded_triangle(&mut fb, tri, [1.0; 3], false, BlendMode::Alpha, |_| Some((0xFFFFFFFF, 0.5)));
+        }
+
+        for (x, y) in [(2, 2), (3, 3), (4, 4), (5, 2), (2, 5)] {
+            assert_eq!(fb.get_pixel(x, y), Some(0xFF7F7F7F), "({x}, {y})");
+        }
+    }
+}
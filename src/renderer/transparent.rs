@@ -0,0 +1,80 @@
+use crate::framebuffer::{Framebuffer, BlendMode};
+use crate::renderer::pipeline::Pipeline;
+
+type DrawFn<'a> = Box<dyn FnOnce(&mut Framebuffer, &mut Pipeline) + 'a>;
+
+/// Superficies translúcidas del frame. Se acumulan mientras se dibuja lo opaco
+/// y se dibujan al final, de la más lejana a la más cercana
+#[derive(Default)]
+pub struct TransparentPass<'a> {
+    items: Vec<(f32, DrawFn<'a>)>,
+}
+
+impl<'a> TransparentPass<'a> {
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    /// Agrega una superficie a `distance` de la cámara. `draw` elige su modo de
+    /// mezcla con `Pipeline::set_blend_mode`
+    pub fn push(&mut self, distance: f32, draw: impl FnOnce(&mut Framebuffer, &mut Pipeline) + 'a) {
+        self.items.push((distance, Box::new(draw)));
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Dibuja todo de atrás hacia adelante y deja el pipeline en modo opaco
+    pub fn flush(mut self, fb: &mut Framebuffer, pipeline: &mut Pipeline) {
+        self.items.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (_, draw) in self.items {
+            draw(fb, pipeline);
+        }
+        pipeline.set_blend_mode(BlendMode::Opaque);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_flush_draws_back_to_front() {
+        let order = RefCell::new(Vec::new());
+        let mut pass = TransparentPass::new();
+        for distance in [5.0, 20.0, 1.0, 10.0] {
+            let order = &order;
+            pass.push(distance, move |_, pipeline| {
+                pipeline.set_blend_mode(BlendMode::Additive);
+                order.borrow_mut().push(distance);
+            });
+        }
+        assert_eq!(pass.len(), 4);
+
+        let mut fb = Framebuffer::new(1, 1);
+        let mut pipeline = Pipeline::new(1, 1);
+        pass.flush(&mut fb, &mut pipeline);
+        assert_eq!(*order.borrow(), vec![20.0, 10.0, 5.0, 1.0]);
+        assert_eq!(pipeline.blend_mode, BlendMode::Opaque);
+    }
+
+    #[test]
+    fn test_alpha_layers_composite_in_order() {
+        // Dos capas al 50%: el resultado depende del orden, la cercana domina
+        let mut fb = Framebuffer::new(1, 1);
+        fb.clear(0xFF000000);
+        let mut pass = TransparentPass::new();
+        pass.push(1.0, |fb: &mut Framebuffer, _: &mut Pipeline| fb.blend_pixel(0, 0, 0xFFFF0000, 0.5, BlendMode::Alpha));
+        pass.push(2.0, |fb: &mut Framebuffer, _: &mut Pipeline| fb.blend_pixel(0, 0, 0xFF0000FF, 0.5, BlendMode::Alpha));
+        pass.flush(&mut fb, &mut Pipeline::new(1, 1));
+        assert_eq!(fb.get_pixel(0, 0), Some(0xFF7F003F));
+    }
+}
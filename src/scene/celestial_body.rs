@@ -1,50 +1,117 @@
-use crate::math::{Vec3, Mat4, create_transform_matrix};
+use crate::math::{Vec3, DVec3, Mat4, create_transform_matrix};
+use crate::models::Ring;
+use crate::effects::Atmosphere;
 
 pub struct CelestialBody {
     pub name: String,
     pub radius: f32,           // escala del planeta
-    pub orbit_radius: f32,     // distancia al sol
-    pub orbit_speed: f32,      // velocidad angular (rotación orbital)
+    pub mu: f64,               // parámetro gravitacional (G * masa)
+    pub orbit_radius: f64,     // distancia al sol
+    pub orbit_speed: f64,      // velocidad angular (rotación orbital)
     pub rotation_speed: f32,   // velocidad angular (rotación propia)
-    pub orbit_angle: f32,      // estado actual de la órbita
+    pub orbit_angle: f64,      // estado actual de la órbita
     pub self_rotation: f32,    // estado actual de la rotación propia
     pub color: u32,            // color del planeta
+    pub emission: f32,         // brillo propio (HDR) para el bloom; 0 si no emite luz
+    pub ring: Option<Ring>,    // anillos (en radios del planeta)
+    pub atmosphere: Option<Atmosphere>,
 }
 
 impl CelestialBody {
-    pub fn new(name: &str, radius: f32, orbit_radius: f32, orbit_speed: f32, rotation_speed: f32, color: u32) -> Self {
+    pub fn new(name: &str, radius: f32, mu: f64, orbit_radius: f64, orbit_speed: f64, rotation_speed: f32, color: u32) -> Self {
         Self {
             name: name.to_string(),
             radius,
+            mu,
             orbit_radius,
             orbit_speed,
             rotation_speed,
             orbit_angle: 0.0,
             self_rotation: 0.0,
             color,
+            emission: 0.0,
+            ring: None,
+            atmosphere: None,
         }
     }
 
+    /// Velocidad angular de una órbita circular de radio `orbit_radius`
+    /// alrededor de un cuerpo con parámetro gravitacional `parent_mu`
+    pub fn circular_orbit_speed(parent_mu: f64, orbit_radius: f64) -> f64 {
+        if orbit_radius <= 0.0 {
+            return 0.0;
+        }
+        (parent_mu / orbit_radius.powi(3)).sqrt()
+    }
+
     /// Actualiza órbita + rotación interna
     pub fn update(&mut self, dt: f32) {
-        self.orbit_angle += self.orbit_speed * dt;
+        self.orbit_angle += self.orbit_speed * dt as f64;
         self.self_rotation += self.rotation_speed * dt;
     }
 
-    /// Retorna la posición en el espacio 3D
-    pub fn position(&self) -> Vec3 {
-        Vec3::new(
-            self.orbit_radius * self.orbit_angle.cos(),
+    /// Retorna la posición en el espacio 3D (coordenadas de mundo, f64)
+    pub fn position(&self) -> DVec3 {
+        self.position_at(0.0)
+    }
+
+    /// Posición dentro de `dt` segundos (las órbitas son deterministas)
+    pub fn position_at(&self, dt: f64) -> DVec3 {
+        let angle = self.orbit_angle + self.orbit_speed * dt;
+        DVec3::new(
+            self.orbit_radius * angle.cos(),
             0.0,
-            self.orbit_radius * self.orbit_angle.sin(),
+            self.orbit_radius * angle.sin(),
         )
     }
 
-    /// Retorna la matriz de modelo para el planeta
-    pub fn model_matrix(&self) -> Mat4 {
-        let pos = self.position();
+    /// Velocidad orbital actual (derivada de `position`)
+    pub fn velocity(&self) -> DVec3 {
+        self.velocity_at(0.0)
+    }
+
+    /// Velocidad orbital dentro de `dt` segundos
+    pub fn velocity_at(&self, dt: f64) -> DVec3 {
+        let angle = self.orbit_angle + self.orbit_speed * dt;
+        let speed = self.orbit_radius * self.orbit_speed;
+        DVec3::new(-speed * angle.sin(), 0.0, speed * angle.cos())
+    }
+
+    /// Puntos de la órbita completa (círculo alrededor del origen)
+    pub fn orbit_points(&self, segments: usize) -> Vec<DVec3> {
+        (0..=segments)
+            .map(|i| {
+                let angle = i as f64 / segments as f64 * std::f64::consts::TAU;
+                DVec3::new(
+                    self.orbit_radius * angle.cos(),
+                    0.0,
+                    self.orbit_radius * angle.sin(),
+                )
+            })
+            .collect()
+    }
+
+    /// Retorna la matriz de modelo relativa a `origin` (normalmente la cámara).
+    /// La resta se hace en f64 para no perder precisión lejos del origen
+    pub fn model_matrix(&self, origin: DVec3) -> Mat4 {
+        let pos = (self.position() - origin).to_vec3();
         let rot = Vec3::new(0.0, self.self_rotation, 0.0);
         let scale = Vec3::new(self.radius, self.radius, self.radius);
         create_transform_matrix(pos, rot, scale)
     }
+
+    /// Matriz de modelo de la cáscara de atmósfera relativa a `origin`
+    pub fn atmosphere_matrix(&self, origin: DVec3) -> Option<Mat4> {
+        let scale = self.radius * self.atmosphere.as_ref()?.shell_radius();
+        let pos = (self.position() - origin).to_vec3();
+        Some(Mat4::translation(pos.x, pos.y, pos.z) * Mat4::scale(scale, scale, scale))
+    }
+
+    /// Matriz de modelo de los anillos relativa a `origin`. Los anillos no giran
+    /// con el planeta: solo se inclinan y se escalan por su radio
+    pub fn ring_matrix(&self, origin: DVec3) -> Option<Mat4> {
+        let ring = self.ring.as_ref()?;
+        let pos = (self.position() - origin).to_vec3();
+        Some(Mat4::translation(pos.x, pos.y, pos.z) * ring.rotation() * Mat4::scale(self.radius, self.radius, self.radius))
+    }
 }
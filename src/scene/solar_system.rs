@@ -1,32 +1,93 @@
 use crate::scene::celestial_body::CelestialBody;
 use crate::renderer::pipeline::Pipeline;
-use crate::framebuffer::Framebuffer;
-use crate::math::{Mat4};
+use crate::renderer::lines::{draw_polyline_3d, LineFade};
+use crate::renderer::transparent::TransparentPass;
+use crate::framebuffer::{Framebuffer, BlendMode, lerp_color};
+use crate::math::{Mat4, Vec3, Vec4, DVec3, world_to_screen, screen_to_world};
 use crate::models::mesh::Mesh;
+use crate::models::{Ring, in_sphere_shadow};
+use crate::effects::Atmosphere;
+use crate::physics::{Bvh, PickRay, PickHit};
+
+/// Color del contorno y del tinte del cuerpo seleccionado
+const SELECTION_COLOR: u32 = 0xFFFFFFFF;
+/// Oscurecimiento máximo de las sombras entre planeta y anillos
+const RING_SHADOW_STRENGTH: f32 = 0.75;
 
 pub struct SolarSystem {
     pub sun: CelestialBody,
     pub planets: Vec<CelestialBody>,
     pub sphere_mesh: Mesh, // malla de esfera low-poly
+    pub sphere_collider: Bvh, // la misma esfera, para colisiones malla-malla
+    pub selected: Option<usize>, // índice como en `body`
 }
 
 impl SolarSystem {
     pub fn new(sphere_mesh: Mesh) -> Self {
-        let sun = CelestialBody::new("Sun", 4.0, 0.0, 0.0, 0.3, 0xFFFFDD44);
+        let sun_mu = 160.0;
+        let mut sun = CelestialBody::new("Sun", 4.0, sun_mu, 0.0, 0.0, 0.3, 0xFFFFDD44);
+        sun.emission = 2.5;
 
+        // Velocidades keplerianas: los planetas caen libremente alrededor del sol,
+        // así una órbita alrededor de un planeta no deriva por fuerzas de marea falsas
+        let kepler = |r: f64| CelestialBody::circular_orbit_speed(sun_mu, r);
         let mut planets = vec![
-            CelestialBody::new("PlanetA", 1.5, 10.0, 0.4, 0.8, 0xFF44AAFF),
-            CelestialBody::new("PlanetB", 1.0, 16.0, 0.3, 1.2, 0xFFFF8844),
-            CelestialBody::new("PlanetC", 2.5, 24.0, 0.1, 0.4, 0xFF88FF44),
+            CelestialBody::new("PlanetA", 1.5, 4.0, 10.0, kepler(10.0), 0.8, 0xFF44AAFF),
+            CelestialBody::new("PlanetB", 1.0, 2.0, 16.0, kepler(16.0), 1.2, 0xFFFF8844),
+            CelestialBody::new("PlanetC", 2.5, 60.0, 24.0, kepler(24.0), 0.4, 0xFF88FF44),
         ];
+        planets[0].atmosphere = Some(Atmosphere::new(0.03, 4.0, 0xFF5C9DFF));
+        planets[2].atmosphere = Some(Atmosphere::new(0.04, 2.5, 0xFFB8D0A0));
+        planets[2].ring = Some(Ring::new(1.4, 2.4, 96, 0.45, 0xFFD8C8A0));
 
         Self {
             sun,
             planets,
+            sphere_collider: Bvh::build(&sphere_mesh),
             sphere_mesh,
+            selected: None,
         }
     }
 
+    /// Todos los cuerpos: el sol (índice 0) seguido de los planetas
+    pub fn bodies(&self) -> impl Iterator<Item = &CelestialBody> {
+        std::iter::once(&self.sun).chain(self.planets.iter())
+    }
+
+    /// Cuerpo por índice, con la misma numeración que `bodies`
+    pub fn body(&self, index: usize) -> Option<&CelestialBody> {
+        match index {
+            0 => Some(&self.sun),
+            i => self.planets.get(i - 1),
+        }
+    }
+
+    /// Cantidad de cuerpos (sol incluido)
+    pub fn body_count(&self) -> usize {
+        1 + self.planets.len()
+    }
+
+    /// Índice del cuerpo con ese nombre, sin distinguir mayúsculas
+    pub fn find_body(&self, name: &str) -> Option<usize> {
+        self.bodies().position(|body| body.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Cuerpo seleccionado (con el mouse o desde otros sistemas)
+    pub fn selected_body(&self) -> Option<&CelestialBody> {
+        self.selected.and_then(|i| self.body(i))
+    }
+
+    /// Cuerpo más cercano que atraviesa el rayo
+    pub fn pick(&self, ray: &PickRay) -> Option<PickHit> {
+        self.bodies()
+            .enumerate()
+            .filter_map(|(i, body)| {
+                let distance = ray.intersect_sphere(body.position(), body.radius as f64)?;
+                Some(PickHit { body: i, point: ray.at(distance), distance })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
     /// Actualiza todos los cuerpos del sistema solar
     pub fn update(&mut self, dt: f32) {
         self.sun.update(dt);
@@ -35,32 +96,311 @@ impl SolarSystem {
         }
     }
 
-    /// Renderiza todos los cuerpos usando la malla de esfera
-    pub fn render(&self, fb: &mut Framebuffer, pipeline: &mut Pipeline, view: Mat4, projection: Mat4) {
-        
-        // Render Sun
-        self.draw_body(&self.sun, fb, pipeline, view, projection);
+    /// Renderiza todos los cuerpos usando la malla de esfera.
+    /// `view` no lleva traslación: las posiciones se hacen relativas a `camera_pos`
+    pub fn render(&self, fb: &mut Framebuffer, pipeline: &mut Pipeline, view: Mat4, projection: Mat4, camera_pos: DVec3) {
+        for (i, body) in self.bodies().enumerate() {
+            self.draw_body(body, self.selected == Some(i), fb, pipeline, view, projection, camera_pos);
+        }
+
+        if let Some(body) = self.selected_body() {
+            draw_outline(body, fb, projection * view, camera_pos);
+        }
+    }
+
+    /// Agrega las partes translúcidas de los cuerpos (atmósferas y anillos) al
+    /// pase transparente. La atmósfera va antes que los anillos del mismo cuerpo
+    pub fn queue_transparent<'a>(&'a self, pass: &mut TransparentPass<'a>, view_proj: Mat4, camera_pos: DVec3) {
+        for body in self.bodies() {
+            let distance = (body.position() - camera_pos).length() as f32;
+            if let Some(atmosphere) = &body.atmosphere {
+                let behind = distance + body.radius * atmosphere.thickness;
+                pass.push(behind, move |fb, pipeline| self.draw_atmosphere(body, fb, pipeline, view_proj, camera_pos));
+            }
+            if body.ring.is_some() {
+                pass.push(distance, move |fb, pipeline| self.draw_ring(body, fb, pipeline, view_proj, camera_pos));
+            }
+        }
+    }
+
+    /// Dibuja la órbita de cada planeta como polilínea con depth test
+    pub fn render_orbits(&self, fb: &mut Framebuffer, view_proj: Mat4, camera_pos: DVec3, near: f32) {
+        let fade = LineFade { start: 60.0, end: 400.0 };
 
-        // Render planets
         for planet in &self.planets {
-            self.draw_body(planet, fb, pipeline, view, projection);
+            let points: Vec<_> = planet
+                .orbit_points(128)
+                .into_iter()
+                .map(|p| (p - camera_pos).to_vec3())
+                .collect();
+            let color = lerp_color(planet.color, 0xFF000000, 0.55);
+            draw_polyline_3d(fb, &points, &view_proj, near, color, fade);
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn draw_body(
         &self,
         body: &CelestialBody,
+        selected: bool,
         fb: &mut Framebuffer,
         pipeline: &mut Pipeline,
         view: Mat4,
         projection: Mat4,
+        camera_pos: DVec3,
     ) {
-        let model = body.model_matrix();
+        let model = body.model_matrix(camera_pos);
         let mvp = projection * view * model;
 
-        pipeline.set_color(body.color);
+        let color = if selected { lerp_color(body.color, SELECTION_COLOR, 0.35) } else { body.color };
+        pipeline.set_color(color);
         pipeline.set_mvp(mvp);
 
-        pipeline.draw_mesh(fb, &self.sphere_mesh.vertices, &self.sphere_mesh.faces);
+        let Some(ring) = &body.ring else {
+            fb.set_emission_level(body.emission);
+            pipeline.draw_mesh(fb, &self.sphere_mesh.vertices, &self.sphere_mesh.faces);
+            fb.set_emission_level(0.0);
+            return;
+        };
+
+        // Sombra de los anillos sobre el planeta, evaluada por píxel
+        let spin = Mat4::rotation_y(body.self_rotation);
+        let to_light = self.to_light(body);
+        pipeline.draw_mesh_shaded(fb, &self.sphere_mesh.vertices, &self.sphere_mesh.faces, true, |p| {
+            let point = spin.mul_vec4(Vec4::from_direction(p)).to_vec3_no_divide();
+            let shadow = ring.shadow_at(point, to_light) * RING_SHADOW_STRENGTH;
+            Some((lerp_color(color, 0xFF000000, shadow), 1.0))
+        });
+    }
+
+    /// Anillos del cuerpo (si tiene) mezclados sobre la escena, con la sombra del planeta
+    fn draw_ring(&self, body: &CelestialBody, fb: &mut Framebuffer, pipeline: &mut Pipeline, view_proj: Mat4, camera_pos: DVec3) {
+        let (Some(ring), Some(model)) = (&body.ring, body.ring_matrix(camera_pos)) else {
+            return;
+        };
+        pipeline.set_mvp(view_proj * model);
+        pipeline.set_blend_mode(BlendMode::Alpha);
+
+        let tilt = ring.rotation();
+        let to_light = self.to_light(body);
+        pipeline.draw_mesh_shaded(fb, &ring.mesh.vertices, &ring.mesh.faces, false, |p| {
+            let (color, alpha) = ring.sample(p.length())?;
+            let point = tilt.mul_vec4(Vec4::from_direction(p)).to_vec3_no_divide();
+            if in_sphere_shadow(point, to_light, 1.0) {
+                Some((lerp_color(color, 0xFF000000, RING_SHADOW_STRENGTH), alpha))
+            } else {
+                Some((color, alpha))
+            }
+        });
+    }
+
+    /// Halo de atmósfera: se dibuja la cáscara que envuelve al cuerpo y cada píxel
+    /// suma la luz dispersada a lo largo de su rayo de vista
+    fn draw_atmosphere(&self, body: &CelestialBody, fb: &mut Framebuffer, pipeline: &mut Pipeline, view_proj: Mat4, camera_pos: DVec3) {
+        let (Some(atmosphere), Some(model)) = (&body.atmosphere, body.atmosphere_matrix(camera_pos)) else {
+            return;
+        };
+        pipeline.set_mvp(view_proj * model);
+        pipeline.set_blend_mode(BlendMode::Additive);
+
+        // Todo en radios del cuerpo, con el centro en el origen
+        let eye = ((camera_pos - body.position()) / body.radius as f64).to_vec3();
+        let shell = atmosphere.shell_radius();
+        let to_light = self.to_light(body);
+        if eye.length() <= shell {
+            draw_atmosphere_inside(atmosphere, fb, view_proj, eye, to_light, body.radius);
+            return;
+        }
+        pipeline.draw_mesh_shaded(fb, &self.sphere_mesh.vertices, &self.sphere_mesh.faces, true, |p| {
+            atmosphere.scatter(eye, (p * shell - eye).normalize(), to_light)
+        });
+    }
+
+    /// Dirección desde el cuerpo hacia el sol
+    fn to_light(&self, body: &CelestialBody) -> Vec3 {
+        (self.sun.position() - body.position()).to_vec3().normalize()
+    }
+}
+
+/// Atmósfera vista desde adentro de la cáscara. Buena parte de sus triángulos
+/// cruza el plano de la cámara y el pipeline los descarta, así que el halo se
+/// calcula por píxel, como el fondo, y cada rayo termina en lo ya dibujado
+fn draw_atmosphere_inside(atmosphere: &Atmosphere, fb: &mut Framebuffer, view_proj: Mat4, eye: Vec3, to_light: Vec3, radius: f32) {
+    let Some(inv) = view_proj.inverse() else {
+        return;
+    };
+    let (w, h) = (fb.width as f32, fb.height as f32);
+    // La cámara está en el origen: el punto desproyectado es afín en el píxel
+    let ray = |x: f32, y: f32| screen_to_world(x, y, 0.5, &inv, w, h);
+    let (Some(origin), Some(right), Some(down)) = (ray(0.5, 0.5), ray(1.5, 0.5), ray(0.5, 1.5)) else {
+        return;
+    };
+    let (step_x, step_y) = (right - origin, down - origin);
+    let clear = fb.depth_mode.clear_value();
+
+    for y in 0..fb.height {
+        let row = origin + step_y * y as f32;
+        for x in 0..fb.width {
+            let index = y * fb.width + x;
+            let depth = fb.zbuffer[index];
+            let limit = if depth == clear {
+                f32::INFINITY
+            } else {
+                screen_to_world(x as f32 + 0.5, y as f32 + 0.5, depth, &inv, w, h)
+                    .map_or(f32::INFINITY, |p| p.length() / radius)
+            };
+            let dir = (row + step_x * x as f32).normalize();
+            if let Some((color, alpha)) = atmosphere.scatter_to(eye, dir, to_light, limit) {
+                fb.buffer[index] = BlendMode::Additive.blend(fb.buffer[index], color, alpha);
+            }
+        }
+    }
+}
+
+/// Círculo alrededor del contorno proyectado del cuerpo
+fn draw_outline(body: &CelestialBody, fb: &mut Framebuffer, view_proj: Mat4, camera_pos: DVec3) {
+    let center = (body.position() - camera_pos).to_vec3();
+    if center.length_squared() == 0.0 {
+        return;
+    }
+
+    // Un punto del borde, perpendicular a la línea de visión
+    let side = if center.x.abs() < center.length() * 0.9 { Vec3::unit_x() } else { Vec3::unit_y() };
+    let edge = center + center.cross(&side).normalize() * body.radius;
+
+    let (w, h) = (fb.width as f32, fb.height as f32);
+    let (Some((cx, cy, _)), Some((ex, ey, _))) = (
+        world_to_screen(center, &view_proj, w, h),
+        world_to_screen(edge, &view_proj, w, h),
+    ) else {
+        return;
+    };
+
+    let radius = ((ex - cx).powi(2) + (ey - cy).powi(2)).sqrt();
+    fb.draw_circle(cx as i32, cy as i32, radius as i32 + 4, SELECTION_COLOR);
+}
+
+/// Sistema con la esfera más tosca: la base de los tests que solo necesitan los
+/// cuerpos y sus órbitas
+#[cfg(test)]
+pub(crate) fn test_system() -> SolarSystem {
+    SolarSystem::new(crate::models::generate_sphere(4, 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::generate_sphere;
+    use crate::renderer::pipeline::black_frame;
+
+    #[test]
+    fn test_pick_nearest_body() {
+        let system = test_system();
+        let planet = system.planets[0].position();
+
+        // Desde arriba del planeta A, mirando hacia abajo
+        let origin = planet + DVec3::new(0.0, 50.0, 0.0);
+        let down = DVec3::new(0.0, -1.0, 0.0);
+        let hit = system.pick(&PickRay::new(origin, down)).unwrap();
+        assert_eq!(hit.body, 1);
+        assert!((hit.distance - 48.5).abs() < 1e-6);
+        assert!((hit.point - (planet + DVec3::new(0.0, 1.5, 0.0))).length() < 1e-6);
+
+        // Mirando hacia arriba no hay nada
+        assert!(system.pick(&PickRay::new(origin, -down)).is_none());
+    }
+
+    #[test]
+    fn test_find_body_by_name() {
+        let system = test_system();
+        assert_eq!(system.body_count(), 4);
+        assert_eq!(system.find_body("sun"), Some(0));
+        assert_eq!(system.find_body("PLANETC"), Some(3));
+        assert_eq!(system.find_body("Planet"), None);
+    }
+
+    #[test]
+    fn test_selected_body() {
+        let mut system = test_system();
+        assert!(system.selected_body().is_none());
+        system.selected = Some(3);
+        assert_eq!(system.selected_body().unwrap().name, "PlanetC");
+    }
+
+    #[test]
+    fn test_ring_is_blended_over_background() {
+        let mut system = SolarSystem::new(generate_sphere(8, 8));
+        system.planets[1].orbit_angle = std::f64::consts::PI; // PlanetB fuera de cuadro
+        system.planets[2].atmosphere = None;
+        let (w, h) = (64, 64);
+        let (mut fb, mut pipeline) = black_frame(w, h);
+
+        // Sobre PlanetC mirando hacia abajo, con los anillos a la vista
+        let camera_pos = system.planets[2].position() + DVec3::new(0.0, 15.0, 0.0);
+        let view = Mat4::look_at(Vec3::zero(), Vec3::new(0.0, -1.0, 0.0), Vec3::unit_z());
+        let projection = pipeline.projection_matrix(1.0, 0.1, 1000.0);
+        system.render(&mut fb, &mut pipeline, view, projection, camera_pos);
+        let mut pass = TransparentPass::new();
+        system.queue_transparent(&mut pass, projection * view, camera_pos);
+        assert_eq!(pass.len(), 2); // atmósfera de A y anillos de C
+        pass.flush(&mut fb, &mut pipeline);
+
+        let planet = system.planets[2].color;
+        let ring_pixels: Vec<u32> = fb.buffer.iter().copied().filter(|&c| c != 0xFF000000 && c != planet).collect();
+        assert!(ring_pixels.len() > 50);
+        // El perfil nunca es opaco: ningún píxel llega al brillo pleno del anillo
+        assert!(ring_pixels.iter().all(|c| (c >> 16) & 0xFF < 0xD8));
+    }
+
+    #[test]
+    fn test_atmosphere_glows_past_the_limb() {
+        let system = SolarSystem::new(generate_sphere(16, 16));
+        let (w, h) = (64, 64);
+        let (mut fb, mut pipeline) = black_frame(w, h);
+
+        // Entre el sol y PlanetA, mirando al lado de día
+        let planet = system.planets[0].position();
+        let camera_pos = planet - DVec3::new(6.0, 0.0, 0.0);
+        let view = Mat4::look_at(Vec3::zero(), Vec3::unit_x(), Vec3::unit_y());
+        let projection = pipeline.projection_matrix(1.0, 0.1, 1000.0);
+        let view_proj = projection * view;
+        system.render(&mut fb, &mut pipeline, view, projection, camera_pos);
+        let mut pass = TransparentPass::new();
+        system.queue_transparent(&mut pass, view_proj, camera_pos);
+        pass.flush(&mut fb, &mut pipeline);
+
+        // Apenas por encima del borde del planeta hay halo; bien afuera, cielo negro
+        let screen = |p: DVec3| {
+            let (x, y, _) = world_to_screen((p - camera_pos).to_vec3(), &view_proj, w as f32, h as f32).unwrap();
+            fb.get_pixel(x as usize, y as usize).unwrap()
+        };
+        let limb = screen(planet + DVec3::new(0.0, 1.5 * 1.03, 0.0));
+        assert_ne!(limb, 0xFF000000);
+        assert_eq!(screen(planet + DVec3::new(0.0, 1.5 * 1.5, 0.0)), 0xFF000000);
+        // Sobre el disco la atmósfera se suma al color del planeta
+        assert_ne!(screen(planet), system.planets[0].color);
+    }
+
+    #[test]
+    fn test_atmosphere_fills_the_sky_from_inside() {
+        let system = SolarSystem::new(generate_sphere(16, 16));
+        let (w, h) = (64, 64);
+        let (mut fb, mut pipeline) = black_frame(w, h);
+
+        // Apenas sobre la superficie del lado de día, mirando al horizonte
+        let body = &system.planets[0];
+        let up = (system.sun.position() - body.position()).normalize();
+        let camera_pos = body.position() + up * (body.radius as f64 * 1.01);
+        let forward = up.to_vec3().cross(&Vec3::unit_y()).normalize();
+        let view = Mat4::look_at(Vec3::zero(), forward, up.to_vec3());
+        let projection = pipeline.projection_matrix(1.0, 0.1, 1000.0);
+        system.render(&mut fb, &mut pipeline, view, projection, camera_pos);
+        let mut pass = TransparentPass::new();
+        system.queue_transparent(&mut pass, projection * view, camera_pos);
+        pass.flush(&mut fb, &mut pipeline);
+
+        // El cielo sobre el horizonte queda cubierto sin huecos
+        let sky = &fb.buffer[..w * h / 2];
+        assert!(sky.iter().all(|&c| c != 0xFF000000));
     }
 }